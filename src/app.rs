@@ -1,10 +1,30 @@
-use crate::systems::ui::container::{handle_container_events, handle_ui_rebuild};
-use crate::systems::inventory::drag::{handle_left_drag_deposit, handle_right_drag_deposit};
-use crate::systems::inventory::input::{handle_keyboard_input, handle_left_clicks_updated, handle_right_clicks_updated, handle_hotbar_selection, handle_chest_button_clicks};
-use crate::systems::ui::visual::{update_held_item_display, update_slot_visuals, update_selected_item_display};
-use crate::world::inventory::components::{DragState, HeldItem, SelectedHotbarSlot};
+use crate::systems::ui::container::{handle_container_events, sync_container_ui};
+use crate::systems::ui::scroll::scroll_container_views;
+use crate::systems::ui::tooltip::update_item_tooltip;
+use crate::systems::inventory::drag::{handle_left_drag_deposit, handle_right_drag_deposit, handle_shift_drag};
+use crate::systems::inventory::consumable::{apply_consume_effects, handle_item_use};
+use crate::systems::inventory::crafting::update_crafting_outputs;
+use crate::systems::inventory::durability::handle_item_damage;
+use crate::systems::inventory::furnace::tick_furnaces;
+use crate::systems::item::definitions::{load_item_definitions, sync_item_definitions};
+use crate::systems::item::world_item::{handle_drop_input, handle_drop_outside_click, move_player, pickup_world_items, spawn_dropped_items, spawn_player, update_world_items};
+use crate::systems::inventory::persistence::handle_save_load_input;
+use crate::systems::inventory::portable::{close_moved_portable_containers, remove_orphaned_portable_containers};
+use crate::systems::inventory::input::{handle_keyboard_input, handle_left_clicks_updated, handle_right_clicks_updated, handle_hotbar_selection, handle_chest_button_clicks, handle_undo_redo_input, handle_offhand_swap, handle_sort_button_clicks, handle_middle_clicks};
+use crate::systems::ui::visual::{update_held_item_display, update_slot_visuals, update_selected_item_display, update_furnace_progress_bars, update_item_use_progress_bar};
+use crate::world::crafting::recipe::RecipeRegistry;
+use crate::world::inventory::components::{DebugMode, DragState, HeldItem, LastSlotClick, SelectedHotbarSlot};
+use crate::world::inventory::consumable::{ItemUseState, PlayerVitals};
 use crate::world::inventory::containers::*;
+use crate::world::inventory::furnace::Furnaces;
+use crate::world::inventory::history::InventoryHistory;
+use crate::world::inventory::kind::ContainerKindRegistry;
+use crate::world::inventory::routing::ShiftClickRouting;
+use crate::world::inventory::sort::SortSettings;
 use crate::world::inventory::ui::*;
+use crate::world::item::definitions::{ItemDefinitions, ItemDefinitionsLoader};
+use crate::world::item::registry::ItemRegistry;
+use crate::world::item::world_item::DropItemEvent;
 use bevy::app::AppExit;
 use bevy::prelude::*;
 use bevy::window::{PrimaryWindow, WindowMode};
@@ -25,40 +45,103 @@ pub fn create_app() -> App {
         .insert_resource(HeldItem::default())
         .insert_resource(DragState::default())
         .insert_resource(ContainerManager::default())
+        .insert_resource(ContainerKindRegistry::default())
         .insert_resource(SelectedHotbarSlot::default())
+        .insert_resource(LastSlotClick::default())
+        .insert_resource(ItemRegistry::default())
+        .insert_resource(RecipeRegistry::default())
+        .insert_resource(Furnaces::default())
+        .insert_resource(InventoryHistory::default())
+        .insert_resource(ItemUseState::default())
+        .insert_resource(PlayerVitals::default())
+        .insert_resource(SortSettings::default())
+        .insert_resource(ShiftClickRouting::default())
+        .insert_resource(DebugMode::default())
+
+        .init_asset::<ItemDefinitions>()
+        .init_asset_loader::<ItemDefinitionsLoader>()
 
         .add_event::<OpenInventoryEvent>()
         .add_event::<CloseInventoryEvent>()
         .add_event::<OpenChestEvent>()
         .add_event::<CloseChestEvent>()
         .add_event::<SwitchChestEvent>()
+        .add_event::<OpenCraftingTableEvent>()
+        .add_event::<CloseCraftingTableEvent>()
+        .add_event::<OpenFurnaceEvent>()
+        .add_event::<CloseFurnaceEvent>()
+        .add_event::<OpenCustomContainerEvent>()
+        .add_event::<CloseCustomContainerEvent>()
+        .add_event::<OpenPortableContainerEvent>()
+        .add_event::<DamageItemEvent>()
+        .add_event::<ItemBrokenEvent>()
+        .add_event::<SlotChangedEvent>()
+        .add_event::<ItemPickedUpEvent>()
+        .add_event::<ItemDepositedEvent>()
+        .add_event::<StackMergedEvent>()
+        .add_event::<ItemsCraftedEvent>()
+        .add_event::<ItemConsumedEvent>()
+        .add_event::<DropItemEvent>()
 
-        .add_systems(Startup, setup_game)
+        .add_systems(Startup, (setup_game, load_item_definitions, spawn_player))
 
         .add_systems(Update, exit_handler)
         .add_systems(Update, toggle_fullscreen)
+        .add_systems(Update, toggle_debug_mode)
+        .add_systems(Update, sync_item_definitions)
+
+        // World items
+        .add_systems(Update, (
+            move_player,
+            update_world_items,
+            spawn_dropped_items,
+            pickup_world_items,
+        ).chain())
 
         .add_systems(Update, (
             handle_keyboard_input,
             handle_hotbar_selection,
+            handle_save_load_input,
             handle_container_events,
-            handle_ui_rebuild,
+            sync_container_ui,
         ).chain()) // Run these in order
 
         .add_systems(Update, (
             handle_left_clicks_updated,
             handle_left_drag_deposit,
+            handle_shift_drag.before(handle_left_clicks_updated),
             
             handle_right_clicks_updated,
             handle_right_drag_deposit,
 
             handle_chest_button_clicks,
+            handle_sort_button_clicks,
+            handle_middle_clicks,
+
+            handle_undo_redo_input,
+            handle_offhand_swap,
+            handle_drop_input,
+            handle_drop_outside_click,
+
+            handle_item_damage,
+            handle_item_use,
+            apply_consume_effects.after(handle_item_use),
+
+            update_crafting_outputs,
+            tick_furnaces,
+            (close_moved_portable_containers, remove_orphaned_portable_containers).chain(),
 
             // Visual updates
-            update_slot_visuals,
-            update_held_item_display,
-            update_selected_item_display,
-        ).after(handle_ui_rebuild)); // run after UI is rebuilt
+            (
+                update_slot_visuals,
+                update_held_item_display,
+                update_selected_item_display,
+                update_furnace_progress_bars,
+                update_item_use_progress_bar,
+                scroll_container_views,
+                update_item_tooltip,
+            ),
+        ).after(sync_container_ui)); // run after the UI is synced
 
     app
 }
@@ -88,4 +171,13 @@ fn toggle_fullscreen(
             window.resolution.set(1280.0, 720.0);
         }
     }
+}
+
+fn toggle_debug_mode(
+    keys: Res<ButtonInput<KeyCode>>,
+    mut debug_mode: ResMut<DebugMode>,
+) {
+    if keys.just_pressed(KeyCode::F3) {
+        debug_mode.enabled = !debug_mode.enabled;
+    }
 }
\ No newline at end of file
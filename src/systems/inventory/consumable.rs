@@ -0,0 +1,115 @@
+use crate::systems::inventory::events::InventoryEventWriters;
+use crate::utils::slot_finder::find_slot_under_cursor;
+use crate::world::inventory::components::{HeldItem, InventorySlot, SelectedHotbarSlot};
+use crate::world::inventory::consumable::{consume_from_slot, ItemUse, ItemUseState, PlayerVitals};
+use crate::world::inventory::containers::{ContainerManager, ContainerType, ItemConsumedEvent, UIMode};
+use crate::world::inventory::history::InventoryHistory;
+use crate::world::item::registry::ItemRegistry;
+use crate::world::item::world_item::DropItemEvent;
+use bevy::input::ButtonInput;
+use bevy::log::info;
+use bevy::prelude::{EventReader, EventWriter, GlobalTransform, MouseButton, Node, Query, Res, ResMut, Time, Window};
+
+/// Holding right-click outside of the inventory uses the selected consumable
+///
+/// The item is consumed once it has been used for its `use_duration_ms`, letting go,
+/// switching slots or opening a container cancels the use.
+pub fn handle_item_use(
+    time: Res<Time>,
+    mouse: Res<ButtonInput<MouseButton>>,
+    selected_hotbar_slot: Res<SelectedHotbarSlot>,
+    mut use_state: ResMut<ItemUseState>,
+    mut container_manager: ResMut<ContainerManager>,
+    item_registry: Res<ItemRegistry>,
+    slot_query: Query<(&InventorySlot, &GlobalTransform, &Node)>,
+    windows: Query<&Window>,
+    history: Res<InventoryHistory>,
+    held_item: Res<HeldItem>,
+    mut consumed_events: EventWriter<ItemConsumedEvent>,
+    mut drop_events: EventWriter<DropItemEvent>,
+    mut inventory_events: InventoryEventWriters,
+) {
+    if container_manager.ui_mode != UIMode::HotbarOnly || !mouse.pressed(MouseButton::Right) {
+        use_state.cancel();
+        return;
+    }
+
+    let slot_index = selected_hotbar_slot.slot_index;
+    let selected_item = container_manager.get_container(&ContainerType::Hotbar)
+        .and_then(|hotbar| hotbar.get_slot(slot_index))
+        .and_then(|stack| stack.item)
+        .filter(|item| item.properties.is_consumable);
+
+    let Some(selected_item) = selected_item else {
+        use_state.cancel();
+        return;
+    };
+
+    let same_use = use_state.current.as_ref()
+        .is_some_and(|current| current.slot_index == slot_index && current.item == selected_item);
+
+    if !same_use {
+        // right-clicking the hotbar itself moves items instead
+        let over_slot = windows.get_single().ok()
+            .and_then(|window| window.cursor_position())
+            .is_some_and(|cursor_pos| find_slot_under_cursor(cursor_pos, &slot_query).is_some());
+
+        if over_slot {
+            use_state.cancel();
+            return;
+        }
+
+        use_state.current = Some(ItemUse::new(slot_index, selected_item));
+    }
+
+    let Some(current) = use_state.current.as_mut() else { return; };
+    current.elapsed += time.delta_seconds();
+    if !current.is_finished() {
+        return;
+    }
+
+    // keep eating while the button stays held, like Minecraft
+    use_state.cancel();
+
+    // eating can't be undone, only report the slots it changed
+    let pending = history.begin(&mut container_manager, &held_item);
+    let consumed = consume_from_slot(&mut container_manager, &ContainerType::Hotbar, slot_index, &item_registry);
+
+    for change in &history.discard(pending, &mut container_manager, &held_item).changes {
+        inventory_events.send_slot_change(change);
+    }
+
+    let Some(consumed) = consumed else { return; };
+
+    // no room for the remainder, throw it on the ground instead
+    if let Some(leftover) = consumed.leftover {
+        drop_events.send(DropItemEvent { stack: leftover });
+    }
+
+    consumed_events.send(ItemConsumedEvent {
+        container: ContainerType::Hotbar,
+        index: slot_index,
+        item: consumed.item,
+        effects: consumed.item.properties.consume_effects,
+        remainder: consumed.remainder,
+    });
+}
+
+/// Example subscriber to `ItemConsumedEvent`, restoring the player's health and hunger
+pub fn apply_consume_effects(
+    mut consumed_events: EventReader<ItemConsumedEvent>,
+    mut vitals: ResMut<PlayerVitals>,
+) {
+    for event in consumed_events.read() {
+        for effect in event.effects {
+            if !vitals.apply(effect) {
+                info!("{} has an effect the player vitals don't handle: {:?}", event.item.display_name, effect);
+            }
+        }
+
+        info!(
+            "Consumed {}, health {}/{}, hunger {}/{}",
+            event.item.display_name, vitals.health, vitals.max_health, vitals.hunger, vitals.max_hunger
+        );
+    }
+}
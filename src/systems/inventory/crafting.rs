@@ -0,0 +1,46 @@
+use crate::systems::inventory::events::InventoryEventWriters;
+use crate::utils::crafting::refresh_crafting_output;
+use crate::world::crafting::recipe::RecipeRegistry;
+use crate::world::inventory::containers::{ContainerManager, ContainerType};
+use crate::world::inventory::history::SlotChange;
+use crate::world::item::registry::ItemRegistry;
+use bevy::prelude::{DetectChanges, Res, ResMut};
+
+/// Keeps every crafting output slot in sync with the recipe matching its grid
+pub fn update_crafting_outputs(
+    mut container_manager: ResMut<ContainerManager>,
+    recipes: Res<RecipeRegistry>,
+    item_registry: Res<ItemRegistry>,
+    mut inventory_events: InventoryEventWriters,
+) {
+    if !container_manager.is_changed() && !recipes.is_changed() && !item_registry.is_changed() {
+        return;
+    }
+
+    let stations: Vec<_> = container_manager.containers.keys()
+        .filter_map(|container_type| match container_type {
+            ContainerType::CraftingGrid(station) => Some(*station),
+            _ => None,
+        })
+        .collect();
+
+    for station in stations {
+        let output_type = ContainerType::CraftingOutput(station);
+        let output_slot = |container_manager: &ContainerManager| {
+            container_manager.get_container(&output_type).and_then(|output| output.get_slot(0)).cloned()
+        };
+
+        let before = output_slot(&container_manager);
+        refresh_crafting_output(station, &mut container_manager, &recipes, &item_registry);
+        let after = output_slot(&container_manager);
+
+        if before != after {
+            inventory_events.send_slot_change(&SlotChange {
+                container_type: output_type.clone(),
+                slot_index: 0,
+                before,
+                after,
+            });
+        }
+    }
+}
@@ -1,11 +1,16 @@
-use crate::utils::item_operations::{deposit_single_item, filter_valid_distribution_slots, place_stack_in_slot, process_left_click};
+use crate::systems::inventory::events::InventoryEventWriters;
+use crate::utils::crafting::take_crafting_output;
+use crate::utils::item_operations::{deposit_single_item, filter_valid_distribution_slots, place_stack_in_slot, process_left_click, process_shift_click};
 use crate::utils::slot_finder::find_slot_under_cursor;
 use crate::world::inventory::components::{DragState, HeldItem, InventorySlot};
-use crate::world::inventory::containers::ContainerManager;
+use crate::world::inventory::containers::{ContainerManager, ContainerType, CraftingStation};
+use crate::world::inventory::history::InventoryHistory;
 use crate::world::inventory::item_stack::ItemStack;
+use crate::world::inventory::routing::ShiftClickRouting;
 use bevy::input::mouse::MouseButtonInput;
 use bevy::input::ButtonState;
-use bevy::prelude::{EventReader, GlobalTransform, MouseButton, Node, Query, ResMut, Window};
+use bevy::input::ButtonInput;
+use bevy::prelude::{EventReader, GlobalTransform, KeyCode, MouseButton, Node, Query, Res, ResMut, Window};
 
 pub fn handle_left_drag_deposit(
     mouse_events: EventReader<MouseButtonInput>,
@@ -14,6 +19,8 @@ pub fn handle_left_drag_deposit(
     drag_state: ResMut<DragState>,
     slot_query: Query<(&InventorySlot, &GlobalTransform, &Node)>,
     windows: Query<&Window>,
+    history: ResMut<InventoryHistory>,
+    inventory_events: InventoryEventWriters,
 ) {
     handle_drag_deposit(
         mouse_events,
@@ -22,6 +29,8 @@ pub fn handle_left_drag_deposit(
         drag_state,
         slot_query,
         windows,
+        history,
+        inventory_events,
         MouseButton::Left,
     );
 }
@@ -33,6 +42,8 @@ pub fn handle_right_drag_deposit(
     drag_state: ResMut<DragState>,
     slot_query: Query<(&InventorySlot, &GlobalTransform, &Node)>,
     windows: Query<&Window>,
+    history: ResMut<InventoryHistory>,
+    inventory_events: InventoryEventWriters,
 ) {
     handle_drag_deposit(
         mouse_events,
@@ -41,10 +52,114 @@ pub fn handle_right_drag_deposit(
         drag_state,
         slot_query,
         windows,
+        history,
+        inventory_events,
         MouseButton::Right,
     );
 }
 
+/// Holding Shift and dragging with the left button shift-clicks every slot passed over, once each
+///
+/// Starts on a left press with Shift held and nothing on the cursor, so it never overlaps with
+/// the even-distribution left drag. Crafting outputs are left to the regular shift-click.
+///
+/// Every transfer is recorded on its own and merged into one undo step on release, so writes made
+/// by others while the drag is held (furnaces, pickups) don't end up in it.
+pub fn handle_shift_drag(
+    mut mouse_events: EventReader<MouseButtonInput>,
+    keys: Res<ButtonInput<KeyCode>>,
+    mut container_manager: ResMut<ContainerManager>,
+    held_item: Res<HeldItem>,
+    mut drag_state: ResMut<DragState>,
+    slot_query: Query<(&InventorySlot, &GlobalTransform, &Node)>,
+    windows: Query<&Window>,
+    mut history: ResMut<InventoryHistory>,
+    mut inventory_events: InventoryEventWriters,
+    routing: Res<ShiftClickRouting>,
+) {
+    // releases outside the window still end the drag, only hovering needs the cursor
+    let cursor_pos = windows.get_single().ok().and_then(|window| window.cursor_position());
+    let slot_under_cursor = cursor_pos.and_then(|cursor_pos| find_slot_under_cursor(cursor_pos, &slot_query));
+    let on_crafting_output = slot_under_cursor
+        .is_some_and(|slot| matches!(slot.container_type, ContainerType::CraftingOutput(_)));
+    let hovered_slot = slot_under_cursor.filter(|_| !on_crafting_output);
+
+    drag_state.was_shift_dragging_this_frame = false;
+
+    for event in mouse_events.read() {
+        if event.button != MouseButton::Left {
+            continue;
+        }
+
+        match event.state {
+            ButtonState::Pressed => {
+                let shift_pressed = keys.pressed(KeyCode::ShiftLeft) || keys.pressed(KeyCode::ShiftRight);
+                if shift_pressed && held_item.stack.is_none() && !on_crafting_output {
+                    // a drag whose release never arrived still ends here
+                    finish_shift_drag(&mut drag_state, &mut history);
+
+                    drag_state.is_shift_dragging = true;
+
+                    // the pressed slot is transferred right away, the release may come in the same frame
+                    if let Some(slot) = hovered_slot {
+                        shift_drag_over(slot, &mut container_manager, &held_item, &mut drag_state, &history, &mut inventory_events, &routing);
+                    }
+                }
+            }
+            ButtonState::Released => {
+                if !drag_state.is_shift_dragging {
+                    continue;
+                }
+
+                drag_state.is_shift_dragging = false;
+                drag_state.was_shift_dragging_this_frame = true;
+                finish_shift_drag(&mut drag_state, &mut history);
+            }
+        }
+    }
+
+    if !drag_state.is_shift_dragging {
+        return;
+    }
+
+    if let Some(slot) = hovered_slot {
+        shift_drag_over(slot, &mut container_manager, &held_item, &mut drag_state, &history, &mut inventory_events, &routing);
+    }
+}
+
+/// Shift-clicks a slot the shift-drag passed over unless it already did, recording only that transfer
+fn shift_drag_over(
+    slot: &InventorySlot,
+    container_manager: &mut ContainerManager,
+    held_item: &HeldItem,
+    drag_state: &mut DragState,
+    history: &InventoryHistory,
+    inventory_events: &mut InventoryEventWriters,
+    routing: &ShiftClickRouting,
+) {
+    if !drag_state.add_shift_drag_slot(slot.container_type.clone(), slot.index) {
+        return;
+    }
+
+    let transaction = history.begin(container_manager, held_item);
+    process_shift_click(slot.index, &slot.container_type, container_manager, routing);
+    let transaction = history.discard(transaction, container_manager, held_item);
+    inventory_events.send_transaction(&transaction);
+
+    match &mut drag_state.shift_drag_transaction {
+        Some(drag_transaction) => drag_transaction.merge(transaction),
+        None => drag_state.shift_drag_transaction = Some(transaction),
+    }
+}
+
+/// Records everything the shift-drag transferred as one undo step
+fn finish_shift_drag(drag_state: &mut DragState, history: &mut InventoryHistory) {
+    drag_state.shift_drag_slots.clear();
+    if let Some(transaction) = drag_state.shift_drag_transaction.take() {
+        history.push(transaction);
+    }
+}
+
 fn handle_drag_deposit(
     mut mouse_events: EventReader<MouseButtonInput>,
     mut container_manager: ResMut<ContainerManager>,
@@ -52,10 +167,12 @@ fn handle_drag_deposit(
     mut drag_state: ResMut<DragState>,
     slot_query: Query<(&InventorySlot, &GlobalTransform, &Node)>,
     windows: Query<&Window>,
+    mut history: ResMut<InventoryHistory>,
+    mut inventory_events: InventoryEventWriters,
     button: MouseButton,
 ) {
-    let Ok(window) = windows.get_single() else { return; };
-    let Some(cursor_pos) = window.cursor_position() else { return; };
+    // releases outside the window still end the drag, only hovering needs the cursor
+    let cursor_pos = windows.get_single().ok().and_then(|window| window.cursor_position());
     
     let is_left = button == MouseButton::Left;
     
@@ -83,13 +200,22 @@ fn handle_drag_deposit(
                     let is_dragging = if is_left { drag_state.is_left_dragging } else { drag_state.is_right_dragging };
                     
                     if is_dragging {
+                        let transaction = history.begin(&mut container_manager, &held_item);
+
                         if is_left {
                             drag_state.was_left_dragging_this_frame = true;
-                            process_drag_end(&mut container_manager, &mut held_item, &mut drag_state);
+                            let crafted = process_drag_end(&mut container_manager, &mut held_item, &mut drag_state);
+                            if let Some((station, result)) = crafted {
+                                inventory_events.send_crafted(station, result.item.unwrap(), result.size);
+                            }
                         } else {
                             drag_state.was_right_dragging_this_frame = true;
                             process_right_drag_end(&mut container_manager, &mut held_item, &drag_state);
                         }
+
+                        if let Some(transaction) = history.commit(transaction, &mut container_manager, &held_item) {
+                            inventory_events.send_transaction(&transaction);
+                        }
                     }
                     
                     if is_left {
@@ -108,7 +234,7 @@ fn handle_drag_deposit(
     let is_dragging = if is_left { drag_state.is_left_dragging } else { drag_state.is_right_dragging };
     
     if is_dragging && held_item.stack.is_some() {
-        if let Some(slot) = find_slot_under_cursor(cursor_pos, &slot_query) {
+        if let Some(slot) = cursor_pos.and_then(|cursor_pos| find_slot_under_cursor(cursor_pos, &slot_query)) {
             if is_left {
                 drag_state.add_left_drag_slot(slot.container_type.clone(), slot.index);
             } else {
@@ -150,24 +276,27 @@ pub(crate) fn process_right_drag_end(
     }
 }
 
+/// Finishes a left drag, returning what was crafted if the drag ended on a crafting output
 pub(crate) fn process_drag_end(
     container_manager: &mut ContainerManager,
     held_item: &mut HeldItem,
     drag_state: &mut DragState,
-) {
+) -> Option<(CraftingStation, ItemStack)> {
     let Some(_) = &held_item.stack else {
-        return;
+        return None;
     };
 
     // If no slots were dragged over, do nothing (keep holding item)
     if drag_state.left_drag_slots.is_empty() {
-        return;
+        return None;
     }
 
     // Single-deposition
     if drag_state.left_drag_slots.len() == 1 {
         if let Some((container_type, slot_index)) = drag_state.left_drag_slots.first() {
-            if let Some(container) = container_manager.get_container_mut(container_type) {
+            if let ContainerType::CraftingOutput(station) = container_type {
+                return take_crafting_output(*station, container_manager, held_item).map(|result| (*station, result));
+            } else if let Some(container) = container_manager.get_container_mut(container_type) {
                 process_left_click(*slot_index, container, held_item);
             }
         }
@@ -175,6 +304,8 @@ pub(crate) fn process_drag_end(
         // Even-distribution
         distribute_items_evenly(container_manager, held_item, drag_state);
     }
+
+    None
 }
 
 fn distribute_items_evenly(
@@ -246,7 +377,7 @@ fn execute_distribution(
             };
 
             if items_for_this_slot > 0 {
-                let stack_to_place = ItemStack::new(held_stack.item.unwrap(), items_for_this_slot);
+                let stack_to_place = held_stack.with_size(items_for_this_slot);
                 let leftover = place_stack_in_slot(container, slot_index, stack_to_place);
                 let actually_placed = items_for_this_slot - leftover.map(|s| s.size).unwrap_or(0);
                 total_distributed += actually_placed;
@@ -0,0 +1,39 @@
+use crate::systems::inventory::events::InventoryEventWriters;
+use crate::world::inventory::containers::{ContainerManager, DamageItemEvent, ItemBrokenEvent};
+use crate::world::inventory::history::SlotChange;
+use bevy::prelude::{EventReader, EventWriter, ResMut};
+
+/// Applies wear requested by gameplay systems (combat, tool use, etc.) and reports broken items
+pub fn handle_item_damage(
+    mut damage_events: EventReader<DamageItemEvent>,
+    mut broken_events: EventWriter<ItemBrokenEvent>,
+    mut container_manager: ResMut<ContainerManager>,
+    mut inventory_events: InventoryEventWriters,
+) {
+    for event in damage_events.read() {
+        let slot = |container_manager: &ContainerManager| {
+            container_manager.get_container(&event.container_type).and_then(|container| container.get_slot(event.slot_index)).cloned()
+        };
+
+        let before = slot(&container_manager);
+        let broken = container_manager.damage_slot(&event.container_type, event.slot_index, event.amount);
+        let after = slot(&container_manager);
+
+        if before != after {
+            inventory_events.send_slot_change(&SlotChange {
+                container_type: event.container_type.clone(),
+                slot_index: event.slot_index,
+                before,
+                after,
+            });
+        }
+
+        if let Some(item) = broken {
+            broken_events.send(ItemBrokenEvent {
+                container_type: event.container_type.clone(),
+                slot_index: event.slot_index,
+                item,
+            });
+        }
+    }
+}
@@ -0,0 +1,94 @@
+use crate::world::inventory::containers::{CraftingStation, ItemDepositedEvent, ItemPickedUpEvent, ItemsCraftedEvent, SlotChangedEvent, StackMergedEvent};
+use crate::world::inventory::history::{InventoryTransaction, SlotChange};
+use crate::world::inventory::item_stack::ItemStack;
+use crate::world::item::item::Item;
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::EventWriter;
+
+/// Every inventory change event, bundled so systems that edit slots only need one parameter
+#[derive(SystemParam)]
+pub struct InventoryEventWriters<'w> {
+    slot_changed: EventWriter<'w, SlotChangedEvent>,
+    picked_up: EventWriter<'w, ItemPickedUpEvent>,
+    deposited: EventWriter<'w, ItemDepositedEvent>,
+    merged: EventWriter<'w, StackMergedEvent>,
+    crafted: EventWriter<'w, ItemsCraftedEvent>,
+}
+
+impl InventoryEventWriters<'_> {
+
+    /// Sends a `SlotChangedEvent` for every slot the transaction touched,
+    /// plus pick-up / deposit / merge events describing what the player did
+    pub fn send_transaction(&mut self, transaction: &InventoryTransaction) {
+        let held_gain = gained_items(transaction.held_before.as_ref(), transaction.held_after.as_ref());
+
+        for change in &transaction.changes {
+            self.send_slot_change(change);
+
+            // items that left the slot and ended up on the cursor
+            if let (Some(before), Some((held_item, _))) = (&change.before, held_gain) {
+                let removed = match &change.after {
+                    Some(after) if after.item == before.item => before.size.saturating_sub(after.size),
+                    _ => before.size,
+                };
+
+                if removed > 0 && before.item == Some(held_item) {
+                    self.picked_up.send(ItemPickedUpEvent {
+                        container: change.container_type.clone(),
+                        index: change.slot_index,
+                        stack: before.with_size(removed),
+                    });
+                }
+            }
+
+            let Some(after) = &change.after else { continue; };
+            match &change.before {
+                Some(before) if before.item == after.item => {
+                    if after.size > before.size {
+                        self.merged.send(StackMergedEvent {
+                            container: change.container_type.clone(),
+                            index: change.slot_index,
+                            item: after.item.unwrap(),
+                            amount: after.size - before.size,
+                            new_size: after.size,
+                        });
+                    }
+                }
+                _ => {
+                    self.deposited.send(ItemDepositedEvent {
+                        container: change.container_type.clone(),
+                        index: change.slot_index,
+                        stack: after.clone(),
+                    });
+                }
+            }
+        }
+    }
+
+    /// Sends a `SlotChangedEvent` for a change made outside of a transaction (furnaces, crafting outputs, ...)
+    pub fn send_slot_change(&mut self, change: &SlotChange) {
+        self.slot_changed.send(SlotChangedEvent {
+            container: change.container_type.clone(),
+            index: change.slot_index,
+            before: change.before.clone(),
+            after: change.after.clone(),
+        });
+    }
+
+    pub fn send_crafted(&mut self, station: CraftingStation, item: Item, count: u32) {
+        self.crafted.send(ItemsCraftedEvent { station, item, count });
+    }
+}
+
+/// The item and amount the cursor gained, if any
+fn gained_items(before: Option<&ItemStack>, after: Option<&ItemStack>) -> Option<(Item, u32)> {
+    let after = after?;
+    let item = after.item?;
+
+    match before {
+        Some(before) if before.item == after.item => {
+            (after.size > before.size).then(|| (item, after.size - before.size))
+        }
+        _ => Some((item, after.size)),
+    }
+}
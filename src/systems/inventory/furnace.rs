@@ -0,0 +1,48 @@
+use crate::systems::inventory::events::InventoryEventWriters;
+use crate::world::inventory::containers::{ContainerManager, ContainerType};
+use crate::world::inventory::furnace::Furnaces;
+use crate::world::inventory::history::SlotChange;
+use crate::world::item::registry::ItemRegistry;
+use bevy::prelude::{DetectChangesMut, Res, ResMut, Time};
+
+/// Burns fuel and smelts items in every furnace, whether its UI is open or not
+pub fn tick_furnaces(
+    time: Res<Time>,
+    mut furnaces: ResMut<Furnaces>,
+    mut container_manager: ResMut<ContainerManager>,
+    item_registry: Res<ItemRegistry>,
+    mut inventory_events: InventoryEventWriters,
+) {
+    let delta = time.delta_seconds();
+    let mut any_changed = false;
+
+    // only flag the manager as changed when a slot actually changed, other systems react to it
+    let manager = container_manager.bypass_change_detection();
+    for (container_type, container) in manager.containers.iter_mut() {
+        let ContainerType::Furnace(furnace_id) = container_type else { continue; };
+
+        let before: Vec<_> = (0..container.len()).map(|index| container.get_slot(index).cloned()).collect();
+
+        let state = furnaces.states.entry(*furnace_id).or_default();
+        if !state.tick(delta, container, &item_registry) {
+            continue;
+        }
+
+        any_changed = true;
+        for (slot_index, before) in before.into_iter().enumerate() {
+            let after = container.get_slot(slot_index).cloned();
+            if before != after {
+                inventory_events.send_slot_change(&SlotChange {
+                    container_type: container_type.clone(),
+                    slot_index,
+                    before,
+                    after,
+                });
+            }
+        }
+    }
+
+    if any_changed {
+        container_manager.set_changed();
+    }
+}
@@ -1,11 +1,22 @@
-use crate::utils::item_operations::{process_left_click, process_right_click, process_shift_click};
+use crate::utils::crafting::{craft_all_into_targets, take_crafting_output};
+use crate::utils::item_operations::{gather_onto_held, process_left_click, process_right_click, process_shift_click, swap_with_hotbar, swap_with_offhand};
+use crate::systems::inventory::events::InventoryEventWriters;
 use crate::utils::slot_finder::find_slot_under_cursor;
-use crate::world::inventory::components::{DragState, HeldItem, InventorySlot, SelectedHotbarSlot};
-use crate::world::inventory::containers::{CloseChestEvent, CloseInventoryEvent, ContainerManager, OpenChestEvent, OpenInventoryEvent, SwitchChestEvent};
+use crate::world::inventory::components::{DragState, HeldItem, InventorySlot, LastSlotClick, ScrollView, SelectedHotbarSlot};
+use crate::world::inventory::history::{InventoryHistory, SlotChange};
+use crate::world::crafting::recipe::RecipeRegistry;
+use crate::world::inventory::containers::{UIMode, CloseChestEvent, CloseCraftingTableEvent, CloseCustomContainerEvent, CloseFurnaceEvent, CloseInventoryEvent, ContainerManager, ContainerType, OpenChestEvent, OpenCraftingTableEvent, OpenFurnaceEvent, OpenInventoryEvent, OpenPortableContainerEvent, SwitchChestEvent};
+use crate::world::item::registry::ItemRegistry;
 use bevy::input::mouse::{MouseButtonInput, MouseWheel};
 use bevy::input::{ButtonInput, ButtonState};
-use bevy::prelude::{EventReader, EventWriter, GlobalTransform, KeyCode, MouseButton, Node, Query, Res, ResMut, Window, Interaction, Changed, With};
-use crate::world::inventory::ui::ChestButton;
+use bevy::log::info;
+use bevy::prelude::{EventReader, EventWriter, GlobalTransform, KeyCode, MouseButton, Node, Query, Res, ResMut, Time, Window, Interaction, Changed, With, Rect};
+use crate::world::inventory::routing::ShiftClickRouting;
+use crate::world::inventory::sort::SortSettings;
+use crate::world::inventory::ui::{ChestButton, SortButton};
+
+/// Crafting tables Shift+T cycles through
+const CRAFTING_TABLE_COUNT: u32 = 3;
 
 pub fn handle_keyboard_input(
     keys: Res<ButtonInput<KeyCode>>,
@@ -13,6 +24,11 @@ pub fn handle_keyboard_input(
     mut close_inventory_events: EventWriter<CloseInventoryEvent>,
     mut open_chest_events: EventWriter<OpenChestEvent>,
     mut close_chest_events: EventWriter<CloseChestEvent>,
+    mut open_crafting_table_events: EventWriter<OpenCraftingTableEvent>,
+    mut close_crafting_table_events: EventWriter<CloseCraftingTableEvent>,
+    mut open_furnace_events: EventWriter<OpenFurnaceEvent>,
+    mut close_furnace_events: EventWriter<CloseFurnaceEvent>,
+    mut close_custom_events: EventWriter<CloseCustomContainerEvent>,
     container_manager: Res<ContainerManager>,
 ) {
     if keys.just_pressed(KeyCode::KeyE) {
@@ -26,6 +42,42 @@ pub fn handle_keyboard_input(
             crate::world::inventory::containers::UIMode::ChestOpen(_) => {
                 close_inventory_events.send(CloseInventoryEvent);
             }
+            crate::world::inventory::containers::UIMode::CraftingTableOpen(_) => {
+                close_crafting_table_events.send(CloseCraftingTableEvent);
+            }
+            crate::world::inventory::containers::UIMode::FurnaceOpen(_) => {
+                close_furnace_events.send(CloseFurnaceEvent);
+            }
+            crate::world::inventory::containers::UIMode::CustomOpen(..) => {
+                close_custom_events.send(CloseCustomContainerEvent);
+            }
+        }
+    }
+
+    if keys.just_pressed(KeyCode::KeyT) {
+        let shift_pressed = keys.pressed(KeyCode::ShiftLeft) || keys.pressed(KeyCode::ShiftRight);
+        match container_manager.ui_mode {
+            // Shift+T moves on to the next table, wrapping around after the last one
+            crate::world::inventory::containers::UIMode::CraftingTableOpen(table_id) if shift_pressed => {
+                open_crafting_table_events.send(OpenCraftingTableEvent { table_id: table_id % CRAFTING_TABLE_COUNT + 1 });
+            }
+            crate::world::inventory::containers::UIMode::CraftingTableOpen(_) => {
+                close_crafting_table_events.send(CloseCraftingTableEvent);
+            }
+            _ => {
+                open_crafting_table_events.send(OpenCraftingTableEvent { table_id: 1 }); // Default to table 1
+            }
+        }
+    }
+
+    if keys.just_pressed(KeyCode::KeyG) {
+        match container_manager.ui_mode {
+            crate::world::inventory::containers::UIMode::FurnaceOpen(_) => {
+                close_furnace_events.send(CloseFurnaceEvent);
+            }
+            _ => {
+                open_furnace_events.send(OpenFurnaceEvent { furnace_id: 1 }); // Default to furnace 1
+            }
         }
     }
 
@@ -48,15 +100,32 @@ pub fn handle_keyboard_input(
             crate::world::inventory::containers::UIMode::InventoryOpen => {
                 close_inventory_events.send(CloseInventoryEvent);
             }
+            crate::world::inventory::containers::UIMode::CraftingTableOpen(_) => {
+                close_crafting_table_events.send(CloseCraftingTableEvent);
+            }
+            crate::world::inventory::containers::UIMode::FurnaceOpen(_) => {
+                close_furnace_events.send(CloseFurnaceEvent);
+            }
+            crate::world::inventory::containers::UIMode::CustomOpen(..) => {
+                close_custom_events.send(CloseCustomContainerEvent);
+            }
             _ => {}
         }
     }
 }
 
+/// 1-9 select a hotbar slot, or swap it with the hovered slot while a container is open
 pub fn handle_hotbar_selection(
     keys: Res<ButtonInput<KeyCode>>,
     mut scroll_events: EventReader<MouseWheel>,
     mut selected_hotbar_slot: ResMut<SelectedHotbarSlot>,
+    mut container_manager: ResMut<ContainerManager>,
+    held_item: Res<HeldItem>,
+    slot_query: Query<(&InventorySlot, &GlobalTransform, &Node)>,
+    scroll_view_query: Query<(&ScrollView, &GlobalTransform, &Node)>,
+    windows: Query<&Window>,
+    mut history: ResMut<InventoryHistory>,
+    mut inventory_events: InventoryEventWriters,
 ) {
     // map input keys to slot indexes
     let key_mappings = [
@@ -71,13 +140,37 @@ pub fn handle_hotbar_selection(
         (KeyCode::Digit9, 8),
     ];
 
+    let hovered_slot = windows.get_single().ok()
+        .and_then(|window| window.cursor_position())
+        .and_then(|cursor_pos| find_slot_under_cursor(cursor_pos, &slot_query))
+        .filter(|_| container_manager.ui_mode != UIMode::HotbarOnly);
+
     for (key, slot_index) in key_mappings {
         if keys.just_pressed(key) {
-            selected_hotbar_slot.slot_index = slot_index;
+            if let Some(slot) = hovered_slot {
+                let transaction = history.begin(&mut container_manager, &held_item);
+                swap_with_hotbar(&slot.container_type, slot.index, slot_index, &mut container_manager);
+                if let Some(transaction) = history.commit(transaction, &mut container_manager, &held_item) {
+                    inventory_events.send_transaction(&transaction);
+                }
+            } else {
+                selected_hotbar_slot.slot_index = slot_index;
+            }
             break;
         }
     }
 
+    // the wheel scrolls a tall chest instead while hovering it
+    let cursor_pos = windows.get_single().ok().and_then(|window| window.cursor_position());
+    let over_scroll_view = cursor_pos.is_some_and(|cursor_pos| {
+        scroll_view_query.iter().any(|(view, transform, node)| {
+            view.is_scrollable() && Rect::from_center_size(transform.translation().truncate(), node.size()).contains(cursor_pos)
+        })
+    });
+    if over_scroll_view {
+        scroll_events.clear();
+    }
+
     // mouse wheel scrolling
     for scroll_event in scroll_events.read() {
         if scroll_event.y != 0.0 {
@@ -102,22 +195,54 @@ pub fn handle_left_clicks_updated(
     slot_query: Query<(&InventorySlot, &GlobalTransform, &Node)>,
     windows: Query<&Window>,
     keys: Res<ButtonInput<KeyCode>>,
+    recipes: Res<RecipeRegistry>,
+    item_registry: Res<ItemRegistry>,
+    mut history: ResMut<InventoryHistory>,
+    mut inventory_events: InventoryEventWriters,
+    time: Res<Time>,
+    mut last_click: ResMut<LastSlotClick>,
+    routing: Res<ShiftClickRouting>,
 ) {
     let Ok(window) = windows.get_single() else { return; };
     let Some(cursor_pos) = window.cursor_position() else { return; };
 
     for event in mouse_events.read() {
         if event.button == MouseButton::Left && event.state == ButtonState::Released {
-            if !drag_state.is_left_dragging && !drag_state.was_left_dragging_this_frame {
+            let was_dragging = drag_state.is_left_dragging || drag_state.was_left_dragging_this_frame
+                || drag_state.is_shift_dragging || drag_state.was_shift_dragging_this_frame;
+            if !was_dragging {
                 if let Some(slot) = find_slot_under_cursor(cursor_pos, &slot_query) {
-                    // Check for shift-click
-                    if keys.pressed(KeyCode::ShiftLeft) || keys.pressed(KeyCode::ShiftRight) {
-                        process_shift_click(slot.index, &slot.container_type, &mut container_manager);
+                    let transaction = history.begin(&mut container_manager, &held_item);
+                    let shift_pressed = keys.pressed(KeyCode::ShiftLeft) || keys.pressed(KeyCode::ShiftRight);
+                    let is_double_click = last_click.register((slot.container_type.clone(), slot.index), time.elapsed_seconds());
+
+                    // Double-clicking with a held stack gathers matching items instead of putting it back down
+                    if is_double_click && !shift_pressed && held_item.stack.is_some() {
+                        gather_onto_held(&mut container_manager, &mut held_item);
+                    }
+                    // Output slots can only be taken from
+                    else if let ContainerType::CraftingOutput(station) = slot.container_type {
+                        let crafted = if shift_pressed {
+                            craft_all_into_targets(station, &mut container_manager, &recipes, &item_registry, &routing)
+                        } else {
+                            take_crafting_output(station, &mut container_manager, &mut held_item)
+                                .map(|result| (result.item.unwrap(), result.size))
+                        };
+
+                        if let Some((item, count)) = crafted {
+                            inventory_events.send_crafted(station, item, count);
+                        }
+                    } else if shift_pressed {
+                        process_shift_click(slot.index, &slot.container_type, &mut container_manager, &routing);
                     } else {
                         if let Some(container) = container_manager.get_container_mut(&slot.container_type) {
                             process_left_click(slot.index, container, &mut held_item);
                         }
                     }
+
+                    if let Some(transaction) = history.commit(transaction, &mut container_manager, &held_item) {
+                        inventory_events.send_transaction(&transaction);
+                    }
                 }
             }
         }
@@ -131,6 +256,9 @@ pub fn handle_right_clicks_updated(
     drag_state: Res<DragState>,
     slot_query: Query<(&InventorySlot, &GlobalTransform, &Node)>,
     windows: Query<&Window>,
+    mut history: ResMut<InventoryHistory>,
+    mut inventory_events: InventoryEventWriters,
+    mut open_portable_events: EventWriter<OpenPortableContainerEvent>,
 ) {
     let Ok(window) = windows.get_single() else { return; };
     let Some(cursor_pos) = window.cursor_position() else { return; };
@@ -139,9 +267,31 @@ pub fn handle_right_clicks_updated(
         if event.button == MouseButton::Right && event.state == ButtonState::Released {
             if !drag_state.is_right_dragging && !drag_state.was_right_dragging_this_frame {
                 if let Some(slot) = find_slot_under_cursor(cursor_pos, &slot_query) {
-                    if let Some(container) = container_manager.get_container_mut(&slot.container_type) {
+                    // with an empty cursor, right-clicking a backpack opens it instead of taking half
+                    let holds_storage = container_manager.get_container(&slot.container_type)
+                        .and_then(|container| container.get_slot(slot.index))
+                        .is_some_and(|stack| stack.item.is_some_and(|item| item.properties.storage.is_some()));
+                    if holds_storage && held_item.stack.is_none() {
+                        open_portable_events.send(OpenPortableContainerEvent {
+                            container_type: slot.container_type.clone(),
+                            slot_index: slot.index,
+                        });
+                        continue;
+                    }
+
+                    let transaction = history.begin(&mut container_manager, &held_item);
+
+                    if let ContainerType::CraftingOutput(station) = slot.container_type {
+                        if let Some(result) = take_crafting_output(station, &mut container_manager, &mut held_item) {
+                            inventory_events.send_crafted(station, result.item.unwrap(), result.size);
+                        }
+                    } else if let Some(container) = container_manager.get_container_mut(&slot.container_type) {
                         process_right_click(slot.index, container, &mut held_item);
                     }
+
+                    if let Some(transaction) = history.commit(transaction, &mut container_manager, &held_item) {
+                        inventory_events.send_transaction(&transaction);
+                    }
                 }
             }
         }
@@ -159,4 +309,133 @@ pub fn handle_chest_button_clicks(
             });
         }
     }
-}
\ No newline at end of file
+}
+
+/// Ctrl+Z undoes the last inventory operation, Ctrl+Y (or Ctrl+Shift+Z) redoes it
+pub fn handle_undo_redo_input(
+    keys: Res<ButtonInput<KeyCode>>,
+    mut history: ResMut<InventoryHistory>,
+    mut container_manager: ResMut<ContainerManager>,
+    mut held_item: ResMut<HeldItem>,
+    mut inventory_events: InventoryEventWriters,
+) {
+    let ctrl_pressed = keys.pressed(KeyCode::ControlLeft) || keys.pressed(KeyCode::ControlRight);
+    if !ctrl_pressed {
+        return;
+    }
+
+    let shift_pressed = keys.pressed(KeyCode::ShiftLeft) || keys.pressed(KeyCode::ShiftRight);
+
+    let result = if keys.just_pressed(KeyCode::KeyY) || (keys.just_pressed(KeyCode::KeyZ) && shift_pressed) {
+        history.redo(&mut container_manager, &mut held_item)
+    } else if keys.just_pressed(KeyCode::KeyZ) {
+        history.undo(&mut container_manager, &mut held_item)
+    } else {
+        return;
+    };
+
+    match result {
+        // undo/redo only restores slots, report it as plain slot changes
+        Ok(transaction) => {
+            for change in &transaction.changes {
+                inventory_events.send_slot_change(change);
+            }
+        }
+        Err(error) => info!("Inventory history: {}", error),
+    }
+}
+
+/// F swaps the selected hotbar item with the offhand slot
+pub fn handle_offhand_swap(
+    keys: Res<ButtonInput<KeyCode>>,
+    selected_hotbar_slot: Res<SelectedHotbarSlot>,
+    mut history: ResMut<InventoryHistory>,
+    mut container_manager: ResMut<ContainerManager>,
+    held_item: Res<HeldItem>,
+    mut inventory_events: InventoryEventWriters,
+) {
+    if !keys.just_pressed(KeyCode::KeyF) {
+        return;
+    }
+
+    let transaction = history.begin(&mut container_manager, &held_item);
+    swap_with_offhand(selected_hotbar_slot.slot_index, &mut container_manager);
+
+    if let Some(transaction) = history.commit(transaction, &mut container_manager, &held_item) {
+        inventory_events.send_transaction(&transaction);
+    }
+}
+
+/// The sort button in a container header sorts that container
+pub fn handle_sort_button_clicks(
+    interaction_query: Query<(&Interaction, &SortButton), Changed<Interaction>>,
+    sort_settings: Res<SortSettings>,
+    mut container_manager: ResMut<ContainerManager>,
+    held_item: Res<HeldItem>,
+    mut history: ResMut<InventoryHistory>,
+    mut inventory_events: InventoryEventWriters,
+) {
+    for (interaction, sort_button) in &interaction_query {
+        if *interaction == Interaction::Pressed {
+            sort_with_history(&sort_button.container_type, &sort_settings, &mut container_manager, &held_item, &mut history, &mut inventory_events);
+        }
+    }
+}
+
+/// Middle-click sorts the container under the cursor, Ctrl+middle-click locks or unlocks the slot instead
+pub fn handle_middle_clicks(
+    mut mouse_events: EventReader<MouseButtonInput>,
+    keys: Res<ButtonInput<KeyCode>>,
+    slot_query: Query<(&InventorySlot, &GlobalTransform, &Node)>,
+    windows: Query<&Window>,
+    sort_settings: Res<SortSettings>,
+    mut container_manager: ResMut<ContainerManager>,
+    held_item: Res<HeldItem>,
+    mut history: ResMut<InventoryHistory>,
+    mut inventory_events: InventoryEventWriters,
+) {
+    let Ok(window) = windows.get_single() else { return; };
+    let Some(cursor_pos) = window.cursor_position() else { return; };
+
+    for event in mouse_events.read() {
+        if event.button != MouseButton::Middle || event.state != ButtonState::Released {
+            continue;
+        }
+        let Some(slot) = find_slot_under_cursor(cursor_pos, &slot_query) else { continue; };
+
+        let ctrl_pressed = keys.pressed(KeyCode::ControlLeft) || keys.pressed(KeyCode::ControlRight);
+        if ctrl_pressed {
+            if let Some(container) = container_manager.get_container_mut(&slot.container_type) {
+                let locked = container.is_slot_locked(slot.index);
+                if container.set_slot_locked(slot.index, !locked).is_ok() {
+                    // the contents stay the same, listeners still learn the slot changed
+                    let stack = container.get_slot(slot.index).cloned();
+                    inventory_events.send_slot_change(&SlotChange {
+                        container_type: slot.container_type.clone(),
+                        slot_index: slot.index,
+                        before: stack.clone(),
+                        after: stack,
+                    });
+                }
+            }
+        } else {
+            sort_with_history(&slot.container_type, &sort_settings, &mut container_manager, &held_item, &mut history, &mut inventory_events);
+        }
+    }
+}
+
+fn sort_with_history(
+    container_type: &ContainerType,
+    sort_settings: &SortSettings,
+    container_manager: &mut ContainerManager,
+    held_item: &HeldItem,
+    history: &mut InventoryHistory,
+    inventory_events: &mut InventoryEventWriters,
+) {
+    let transaction = history.begin(container_manager, held_item);
+    container_manager.sort_container(container_type, sort_settings);
+
+    if let Some(transaction) = history.commit(transaction, container_manager, held_item) {
+        inventory_events.send_transaction(&transaction);
+    }
+}
@@ -1,2 +1,9 @@
+pub mod consumable;
+pub mod crafting;
 pub mod drag;
-pub mod input;
\ No newline at end of file
+pub mod durability;
+pub mod events;
+pub mod furnace;
+pub mod input;
+pub mod persistence;
+pub mod portable;
\ No newline at end of file
@@ -0,0 +1,88 @@
+use crate::systems::inventory::events::InventoryEventWriters;
+use crate::systems::ui::container::UISyncNeeded;
+use crate::world::inventory::components::{HeldItem, SelectedHotbarSlot};
+use crate::world::inventory::containers::{ContainerManager, ContainerType};
+use crate::world::inventory::furnace::Furnaces;
+use crate::world::inventory::history::{InventoryHistory, SlotChange};
+use crate::world::inventory::item_stack::ItemStack;
+use crate::world::inventory::kind::ContainerKindRegistry;
+use crate::world::inventory::save::{load_inventory, save_inventory, DEFAULT_SAVE_PATH};
+use crate::world::item::registry::ItemRegistry;
+use bevy::input::ButtonInput;
+use bevy::log::{error, info};
+use bevy::prelude::{Commands, KeyCode, Res, ResMut};
+use std::collections::HashMap;
+
+/// F5 saves the inventory to disk, F9 loads it back
+pub fn handle_save_load_input(
+    keys: Res<ButtonInput<KeyCode>>,
+    item_registry: Res<ItemRegistry>,
+    container_kinds: Res<ContainerKindRegistry>,
+    mut container_manager: ResMut<ContainerManager>,
+    mut furnaces: ResMut<Furnaces>,
+    mut selected_hotbar_slot: ResMut<SelectedHotbarSlot>,
+    mut held_item: ResMut<HeldItem>,
+    mut history: ResMut<InventoryHistory>,
+    mut inventory_events: InventoryEventWriters,
+    mut commands: Commands,
+) {
+    if keys.just_pressed(KeyCode::F5) {
+        match save_inventory(DEFAULT_SAVE_PATH, &container_manager, &furnaces, &selected_hotbar_slot, &held_item) {
+            Ok(()) => info!("Saved inventory to {}", DEFAULT_SAVE_PATH),
+            Err(error) => error!("Failed to save inventory: {}", error),
+        }
+    }
+
+    if keys.just_pressed(KeyCode::F9) {
+        let before = slot_contents(&container_manager);
+
+        match load_inventory(DEFAULT_SAVE_PATH, &item_registry, &container_kinds, &mut container_manager, &mut furnaces, &mut selected_hotbar_slot, &mut held_item) {
+            Ok(()) => {
+                info!("Loaded inventory from {}", DEFAULT_SAVE_PATH);
+                history.clear(); // recorded transactions refer to the replaced state
+                for change in diff_slot_contents(before, &container_manager) {
+                    inventory_events.send_slot_change(&change);
+                }
+                commands.spawn(UISyncNeeded); // chests may have been added or the open chest closed
+            }
+            Err(error) => error!("Failed to load inventory: {}", error),
+        }
+    }
+}
+
+fn slot_contents(container_manager: &ContainerManager) -> HashMap<ContainerType, Vec<Option<ItemStack>>> {
+    container_manager.containers.iter()
+        .map(|(container_type, container)| {
+            let stacks = (0..container.len()).map(|index| container.get_slot(index).cloned()).collect();
+            (container_type.clone(), stacks)
+        })
+        .collect()
+}
+
+/// Every slot a load changed, containers that appeared or disappeared count as empty on the other side
+fn diff_slot_contents(mut before: HashMap<ContainerType, Vec<Option<ItemStack>>>, container_manager: &ContainerManager) -> Vec<SlotChange> {
+    let mut changes = Vec::new();
+
+    for (container_type, container) in &container_manager.containers {
+        let before_slots = before.remove(container_type).unwrap_or_default();
+
+        for slot_index in 0..container.len().max(before_slots.len()) {
+            let before = before_slots.get(slot_index).cloned().flatten();
+            let after = container.get_slot(slot_index).cloned();
+            if before != after {
+                changes.push(SlotChange { container_type: container_type.clone(), slot_index, before, after });
+            }
+        }
+    }
+
+    for (container_type, before_slots) in before {
+        for (slot_index, before) in before_slots.into_iter().enumerate() {
+            if before.is_some() {
+                changes.push(SlotChange { container_type: container_type.clone(), slot_index, before, after: None });
+            }
+        }
+    }
+
+    changes.sort_by(|a, b| (&a.container_type, a.slot_index).cmp(&(&b.container_type, b.slot_index)));
+    changes
+}
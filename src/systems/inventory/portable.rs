@@ -0,0 +1,51 @@
+use crate::systems::ui::container::UISyncNeeded;
+use crate::world::inventory::components::HeldItem;
+use crate::world::inventory::containers::ContainerManager;
+use crate::world::inventory::history::InventoryHistory;
+use crate::world::item::world_item::{DropItemEvent, WorldItem};
+use bevy::ecs::event::ManualEventReader;
+use bevy::prelude::{Commands, DetectChanges, DetectChangesMut, Events, Query, Res, ResMut};
+
+/// Hides a backpack's contents once the backpack is moved, dropped or swapped out of its slot
+pub fn close_moved_portable_containers(
+    mut container_manager: ResMut<ContainerManager>,
+    mut commands: Commands,
+) {
+    if !container_manager.is_changed() {
+        return;
+    }
+
+    // checking doesn't count as a change, other systems react to it
+    if container_manager.bypass_change_detection().close_moved_portable() {
+        container_manager.set_changed();
+        commands.spawn(UISyncNeeded);
+    }
+}
+
+/// Removes the contents of backpacks that no longer exist anywhere
+///
+/// A stack can be in a container, on the cursor, on the ground, in a drop request that wasn't
+/// spawned yet or in the undo history, its contents are kept as long as it is in any of them.
+pub fn remove_orphaned_portable_containers(
+    mut container_manager: ResMut<ContainerManager>,
+    held_item: Res<HeldItem>,
+    history: Res<InventoryHistory>,
+    world_items: Query<&WorldItem>,
+    drop_events: Res<Events<DropItemEvent>>,
+) {
+    if !container_manager.is_changed() && !history.is_changed() {
+        return;
+    }
+
+    // a fresh reader sees every drop request still buffered, spawned or not
+    let mut pending_drops = ManualEventReader::<DropItemEvent>::default();
+    let stacks = held_item.stack.iter()
+        .chain(world_items.iter().map(|world_item| &world_item.stack))
+        .chain(pending_drops.read(&drop_events).map(|event| &event.stack))
+        .chain(history.stacks());
+
+    let manager = container_manager.bypass_change_detection();
+    if manager.remove_orphaned_portables(stacks) > 0 {
+        container_manager.set_changed();
+    }
+}
@@ -0,0 +1,122 @@
+use crate::systems::inventory::events::InventoryEventWriters;
+use crate::world::inventory::components::HeldItem;
+use crate::world::inventory::containers::{ContainerManager, ContainerType};
+use crate::world::inventory::history::InventoryHistory;
+use crate::world::inventory::item_stack::ItemStack;
+use crate::world::item::definitions::ItemDefinitions;
+use crate::world::item::item::Item;
+use crate::world::item::registry::ItemRegistry;
+use crate::world::item::world_item::DropItemEvent;
+use bevy::asset::{AssetEvent, AssetServer, Assets, Handle};
+use bevy::log::{error, info};
+use bevy::prelude::{Commands, EventReader, EventWriter, Res, ResMut, Resource};
+
+/// Data-driven item definitions merged into the registry alongside `define_items!`
+pub const ITEM_DEFINITIONS_PATH: &str = "items/custom.items.ron";
+
+/// Keeps the item definitions asset loaded (and watched for changes)
+#[derive(Resource)]
+pub struct ItemDefinitionsHandle(pub Handle<ItemDefinitions>);
+
+pub fn load_item_definitions(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.insert_resource(ItemDefinitionsHandle(asset_server.load(ITEM_DEFINITIONS_PATH)));
+}
+
+/// Merges item definitions into the registry whenever the asset is loaded or hot-reloaded
+///
+/// Stacks that got too large for a lowered `max_stack_size` keep what still fits, the rest goes
+/// into free space in the player inventory or hotbar and is dropped into the world if there is none.
+pub fn sync_item_definitions(
+    mut asset_events: EventReader<AssetEvent<ItemDefinitions>>,
+    definitions: Res<Assets<ItemDefinitions>>,
+    mut item_registry: ResMut<ItemRegistry>,
+    mut container_manager: ResMut<ContainerManager>,
+    mut held_item: ResMut<HeldItem>,
+    history: Res<InventoryHistory>,
+    mut drop_events: EventWriter<DropItemEvent>,
+    mut inventory_events: InventoryEventWriters,
+) {
+    for event in asset_events.read() {
+        let (AssetEvent::Added { id } | AssetEvent::Modified { id }) = event else {
+            continue;
+        };
+        let Some(definitions) = definitions.get(*id) else {
+            continue;
+        };
+
+        let (merged, errors) = item_registry.merge_definitions(definitions);
+
+        for definition_error in &errors {
+            error!("Skipping item definition: {}", definition_error);
+        }
+        info!("Merged {} data-driven item(s) into the registry", merged.len());
+
+        // stacks copy their item, so point existing ones at the reloaded definitions
+        let updated: Vec<Item> = merged.iter().filter_map(|id| item_registry.get_by_id(*id)).collect();
+        if updated.is_empty() {
+            continue;
+        }
+
+        // reloads can't be undone, only report the slots they changed
+        let pending = history.begin(&mut container_manager, &held_item);
+        let mut overflow = Vec::new();
+
+        for container in container_manager.containers.values_mut() {
+            for slot_index in 0..container.len() {
+                if let Some(mut stack) = container.get_slot_mut(slot_index) {
+                    overflow.extend(refresh_stack(&mut stack, &updated));
+                }
+            }
+        }
+
+        if let Some(stack) = &mut held_item.stack {
+            overflow.extend(refresh_stack(stack, &updated));
+        }
+
+        for stack in overflow {
+            let mut remaining = Some(stack);
+
+            for target_type in [ContainerType::PlayerInventory, ContainerType::Hotbar] {
+                let Some(stack) = remaining.take() else { break; };
+                remaining = match container_manager.get_container_mut(&target_type) {
+                    Some(target) => target.try_place_stack(stack),
+                    None => Some(stack),
+                };
+            }
+
+            if let Some(stack) = remaining {
+                drop_events.send(DropItemEvent { stack });
+            }
+        }
+
+        for change in &history.discard(pending, &mut container_manager, &held_item).changes {
+            inventory_events.send_slot_change(change);
+        }
+    }
+}
+
+/// Points a stack at its reloaded item, returning whatever no longer fits in it as full stacks
+fn refresh_stack(stack: &mut ItemStack, updated: &[Item]) -> Vec<ItemStack> {
+    let Some(current) = stack.item else { return Vec::new(); };
+    let Some(item) = updated.iter().find(|item| item.identifier == current.identifier) else { return Vec::new(); };
+
+    stack.item = Some(*item);
+    stack.durability = match (stack.durability, item.properties.durability) {
+        (Some(durability), Some(max)) => Some(durability.min(max)),
+        (_, max) => max, // durability was added or removed by the reload
+    };
+
+    let max_stack_size = item.properties.max_stack_size;
+    let mut excess = stack.size.saturating_sub(max_stack_size);
+    stack.size = stack.size.min(max_stack_size);
+
+    let mut overflow = Vec::new();
+    while excess > 0 {
+        let size = excess.min(max_stack_size);
+        let mut split = stack.with_size(size);
+        split.contents = None; // the contents stay with the original stack
+        overflow.push(split);
+        excess -= size;
+    }
+    overflow
+}
@@ -0,0 +1,2 @@
+pub mod definitions;
+pub mod world_item;
\ No newline at end of file
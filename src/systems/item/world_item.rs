@@ -0,0 +1,233 @@
+use crate::systems::inventory::events::InventoryEventWriters;
+use crate::utils::item_operations::{take_for_drop, take_held_for_drop};
+use crate::world::inventory::components::{DragState, HeldItem, SelectedHotbarSlot};
+use crate::world::inventory::containers::{ContainerManager, ContainerType, ContainerUI, UIMode};
+use crate::world::inventory::history::{InventoryHistory, SlotChange};
+use crate::world::inventory::ui::{ChestButton, ItemSpritesheet};
+use crate::systems::ui::slot_utils::sprite_coords_to_atlas_index;
+use crate::world::item::world_item::*;
+use bevy::input::mouse::MouseButtonInput;
+use bevy::input::ButtonState;
+use bevy::prelude::*;
+
+/// UI elements that keep a click from dropping the held item
+type UiBlockingDrops = Or<(With<ContainerUI>, With<ChestButton>)>;
+
+/// Where picked up items go, in order
+const PICKUP_TARGETS: [ContainerType; 2] = [ContainerType::Hotbar, ContainerType::PlayerInventory];
+
+pub fn spawn_player(mut commands: Commands) {
+    commands.spawn((
+        SpriteBundle {
+            sprite: Sprite {
+                color: Color::rgb(0.3, 0.5, 0.9),
+                custom_size: Some(Vec2::splat(PLAYER_SIZE)),
+                ..default()
+            },
+            transform: Transform::from_xyz(0.0, 0.0, 1.0),
+            ..default()
+        },
+        Player::default(),
+    ));
+}
+
+/// WASD moves the player while no container is open
+pub fn move_player(
+    time: Res<Time>,
+    keys: Res<ButtonInput<KeyCode>>,
+    container_manager: Res<ContainerManager>,
+    mut player_query: Query<(&mut Transform, &mut Player)>,
+) {
+    if container_manager.ui_mode != UIMode::HotbarOnly {
+        return;
+    }
+
+    let mut direction = Vec2::ZERO;
+    if keys.pressed(KeyCode::KeyW) { direction.y += 1.0; }
+    if keys.pressed(KeyCode::KeyS) { direction.y -= 1.0; }
+    if keys.pressed(KeyCode::KeyA) { direction.x -= 1.0; }
+    if keys.pressed(KeyCode::KeyD) { direction.x += 1.0; }
+
+    let direction = direction.normalize_or_zero();
+    if direction == Vec2::ZERO {
+        return;
+    }
+
+    for (mut transform, mut player) in &mut player_query {
+        transform.translation += (direction * PLAYER_SPEED * time.delta_seconds()).extend(0.0);
+        player.facing = direction;
+    }
+}
+
+/// Q drops one item from the selected hotbar slot, Ctrl+Q the whole stack
+pub fn handle_drop_input(
+    keys: Res<ButtonInput<KeyCode>>,
+    selected_hotbar_slot: Res<SelectedHotbarSlot>,
+    mut container_manager: ResMut<ContainerManager>,
+    history: Res<InventoryHistory>,
+    held_item: Res<HeldItem>,
+    mut drop_events: EventWriter<DropItemEvent>,
+    mut inventory_events: InventoryEventWriters,
+) {
+    if !keys.just_pressed(KeyCode::KeyQ) {
+        return;
+    }
+
+    let whole_stack = keys.pressed(KeyCode::ControlLeft) || keys.pressed(KeyCode::ControlRight);
+
+    // dropped items are gone from the inventory, so this can't be undone
+    let pending = history.begin(&mut container_manager, &held_item);
+    let dropped = container_manager.get_container_mut(&ContainerType::Hotbar)
+        .and_then(|hotbar| take_for_drop(selected_hotbar_slot.slot_index, hotbar, whole_stack));
+
+    for change in &history.discard(pending, &mut container_manager, &held_item).changes {
+        inventory_events.send_slot_change(change);
+    }
+
+    let Some(stack) = dropped else { return; };
+    drop_events.send(DropItemEvent { stack });
+}
+
+/// Clicking outside of every container drops the held item, left-click the whole stack, right-click one
+pub fn handle_drop_outside_click(
+    mut mouse_events: EventReader<MouseButtonInput>,
+    mut held_item: ResMut<HeldItem>,
+    drag_state: Res<DragState>,
+    ui_query: Query<(&GlobalTransform, &Node), UiBlockingDrops>,
+    windows: Query<&Window>,
+    mut drop_events: EventWriter<DropItemEvent>,
+) {
+    let Ok(window) = windows.get_single() else { return; };
+    let Some(cursor_pos) = window.cursor_position() else { return; };
+
+    for event in mouse_events.read() {
+        if event.state != ButtonState::Released || held_item.stack.is_none() {
+            continue;
+        }
+
+        let whole_stack = match event.button {
+            MouseButton::Left if !drag_state.is_left_dragging && !drag_state.was_left_dragging_this_frame => true,
+            MouseButton::Right if !drag_state.is_right_dragging && !drag_state.was_right_dragging_this_frame => false,
+            _ => continue,
+        };
+
+        if is_cursor_over_ui(cursor_pos, &ui_query) {
+            continue;
+        }
+
+        if let Some(stack) = take_held_for_drop(&mut held_item, whole_stack) {
+            drop_events.send(DropItemEvent { stack });
+        }
+    }
+}
+
+fn is_cursor_over_ui(cursor_pos: Vec2, ui_query: &Query<(&GlobalTransform, &Node), UiBlockingDrops>) -> bool {
+    ui_query.iter().any(|(transform, node)| {
+        Rect::from_center_size(transform.translation().truncate(), node.size()).contains(cursor_pos)
+    })
+}
+
+/// Spawns a world item for every dropped stack, thrown in the direction the player faces
+pub fn spawn_dropped_items(
+    mut commands: Commands,
+    mut drop_events: EventReader<DropItemEvent>,
+    spritesheet: Res<ItemSpritesheet>,
+    player_query: Query<(&Transform, &Player)>,
+) {
+    let (origin, facing) = player_query.get_single()
+        .map(|(transform, player)| (transform.translation.truncate(), player.facing))
+        .unwrap_or((Vec2::ZERO, Vec2::X));
+
+    for event in drop_events.read() {
+        let Some(item) = event.stack.item else { continue; };
+        let (sprite_x, sprite_y) = item.sprite_coords;
+
+        commands.spawn((
+            SpriteSheetBundle {
+                texture: spritesheet.texture.clone(),
+                atlas: TextureAtlas {
+                    layout: spritesheet.texture_atlas.clone(),
+                    index: sprite_coords_to_atlas_index(sprite_x, sprite_y),
+                },
+                sprite: Sprite {
+                    custom_size: Some(Vec2::splat(WORLD_ITEM_SIZE)),
+                    ..default()
+                },
+                transform: Transform::from_translation(origin.extend(0.0)),
+                ..default()
+            },
+            WorldItem::thrown(event.stack.clone(), facing),
+        ));
+    }
+}
+
+/// Slides thrown items to a halt and counts down their pickup delay
+pub fn update_world_items(
+    time: Res<Time>,
+    mut item_query: Query<(&mut Transform, &mut WorldItem)>,
+) {
+    let delta = time.delta_seconds();
+
+    for (mut transform, mut world_item) in &mut item_query {
+        world_item.pickup_delay = (world_item.pickup_delay - delta).max(0.0);
+
+        if world_item.velocity != Vec2::ZERO {
+            transform.translation += (world_item.velocity * delta).extend(0.0);
+
+            let slowed = world_item.velocity * (1.0 - WORLD_ITEM_FRICTION * delta).max(0.0);
+            world_item.velocity = if slowed.length() < 1.0 { Vec2::ZERO } else { slowed };
+        }
+    }
+}
+
+/// Merges world items near the player into the hotbar, then the player inventory
+pub fn pickup_world_items(
+    mut commands: Commands,
+    mut container_manager: ResMut<ContainerManager>,
+    player_query: Query<&Transform, With<Player>>,
+    mut item_query: Query<(Entity, &Transform, &mut WorldItem)>,
+    mut inventory_events: InventoryEventWriters,
+) {
+    let Ok(player_transform) = player_query.get_single() else { return; };
+    let player_pos = player_transform.translation.truncate();
+
+    for (entity, transform, mut world_item) in &mut item_query {
+        if !world_item.can_be_picked_up() || transform.translation.truncate().distance(player_pos) > PICKUP_RADIUS {
+            continue;
+        }
+
+        // check before touching anything, a full inventory is the common case for items lying around
+        let room: u32 = PICKUP_TARGETS.iter()
+            .filter_map(|target| container_manager.get_container(target))
+            .map(|container| container.room_for(&world_item.stack))
+            .sum();
+        if room == 0 {
+            continue;
+        }
+
+        let mut remaining = Some(world_item.stack.clone());
+
+        for target in PICKUP_TARGETS {
+            let Some(stack) = remaining.take() else { break; };
+            let Some(container) = container_manager.get_container_mut(&target) else {
+                remaining = Some(stack);
+                continue;
+            };
+
+            // picking up can't be undone, only the slots it wrote are reported
+            let since = container.start_recording();
+            remaining = container.try_place_stack(stack);
+            for (slot_index, before) in container.finish_recording(since) {
+                let after = container.get_slot(slot_index).cloned();
+                if before != after {
+                    inventory_events.send_slot_change(&SlotChange { container_type: target.clone(), slot_index, before, after });
+                }
+            }
+        }
+
+        match remaining {
+            None => commands.entity(entity).despawn(),
+            Some(stack) => world_item.stack = stack,
+        }
+    }
+}
@@ -1,2 +1,3 @@
 pub mod inventory;
+pub mod item;
 pub mod ui;
\ No newline at end of file
@@ -1,10 +1,18 @@
-use crate::world::inventory::containers::{CloseChestEvent, CloseInventoryEvent, ContainerManager, ContainerUI, OpenChestEvent, OpenInventoryEvent, SwitchChestEvent};
-use crate::world::inventory::ui::create_minecraft_ui;
+use crate::world::inventory::components::{FurnaceProgressBar, InventorySlot, ScrollView};
+use crate::world::inventory::containers::{CloseChestEvent, CloseCraftingTableEvent, CloseCustomContainerEvent, CloseFurnaceEvent, CloseInventoryEvent, ContainerLayout, ContainerManager, ContainerType, ContainerUI, OpenChestEvent, OpenCraftingTableEvent, OpenCustomContainerEvent, OpenFurnaceEvent, OpenInventoryEvent, OpenPortableContainerEvent, SwitchChestEvent};
+use crate::world::inventory::kind::ContainerKindRegistry;
+use crate::world::inventory::ui::{chest_button_color, create_chest_button, create_container_ui, ChestButton, ChestSelectionPanel, ContainerSection, ContainerTitle, SortButton};
 use bevy::asset::AssetServer;
-use bevy::prelude::{Commands, Component, DespawnRecursiveExt, Entity, EventReader, Query, Res, ResMut, With};
+use bevy::ecs::system::SystemParam;
+use bevy::hierarchy::{BuildChildren, Children, DespawnRecursiveExt, HierarchyQueryExt};
+use bevy::log::error;
+use bevy::prelude::{Added, BackgroundColor, Commands, Component, Entity, EventReader, Query, Res, ResMut, Text, With};
+use bevy::ui::{Display, Style};
+use std::collections::HashMap;
 
+/// Marks that the container panels no longer match `ContainerManager::layouts`, see `sync_container_ui`
 #[derive(Component)]
-pub struct UIRebuildNeeded;
+pub struct UISyncNeeded;
 
 pub fn handle_container_events(
     mut container_manager: ResMut<ContainerManager>,
@@ -13,56 +21,250 @@ pub fn handle_container_events(
     mut open_chest_events: EventReader<OpenChestEvent>,
     mut close_chest_events: EventReader<CloseChestEvent>,
     mut switch_chest_events: EventReader<SwitchChestEvent>,
+    mut open_crafting_table_events: EventReader<OpenCraftingTableEvent>,
+    mut close_crafting_table_events: EventReader<CloseCraftingTableEvent>,
+    mut open_furnace_events: EventReader<OpenFurnaceEvent>,
+    mut close_furnace_events: EventReader<CloseFurnaceEvent>,
+    mut open_custom_events: EventReader<OpenCustomContainerEvent>,
+    mut close_custom_events: EventReader<CloseCustomContainerEvent>,
+    mut open_portable_events: EventReader<OpenPortableContainerEvent>,
+    container_kinds: Res<ContainerKindRegistry>,
     mut commands: Commands,
 ) {
-    let mut needs_rebuild = false;
+    let mut needs_sync = false;
 
     for _event in open_inventory_events.read() {
         container_manager.open_inventory();
-        needs_rebuild = true;
+        needs_sync = true;
     }
 
     for _event in close_inventory_events.read() {
         container_manager.close_inventory();
-        needs_rebuild = true;
+        needs_sync = true;
     }
 
     for event in open_chest_events.read() {
         container_manager.open_chest(event.chest_id);
-        needs_rebuild = true;
+        needs_sync = true;
     }
 
     for _event in close_chest_events.read() {
         container_manager.close_chest();
-        needs_rebuild = true;
+        needs_sync = true;
     }
 
     for event in switch_chest_events.read() {
         container_manager.switch_chest(event.chest_id);
-        needs_rebuild = true;
+        needs_sync = true;
     }
 
-    if needs_rebuild {
-        commands.spawn(UIRebuildNeeded);
+    for event in open_crafting_table_events.read() {
+        container_manager.open_crafting_table(event.table_id);
+        needs_sync = true;
+    }
+
+    for _event in close_crafting_table_events.read() {
+        container_manager.close_crafting_table();
+        needs_sync = true;
+    }
+
+    for event in open_furnace_events.read() {
+        container_manager.open_furnace(event.furnace_id);
+        needs_sync = true;
+    }
+
+    for _event in close_furnace_events.read() {
+        container_manager.close_furnace();
+        needs_sync = true;
+    }
+
+    for event in open_custom_events.read() {
+        match container_manager.open_custom(&container_kinds, event.kind, event.id) {
+            Ok(()) => needs_sync = true,
+            Err(kind_error) => error!("Can't open {} {}: {}", event.kind.identifier, event.id, kind_error),
+        }
+    }
+
+    for _event in close_custom_events.read() {
+        container_manager.close_custom();
+        needs_sync = true;
+    }
+
+    for event in open_portable_events.read() {
+        needs_sync |= container_manager.open_portable(&container_kinds, &event.container_type, event.slot_index);
+    }
+
+    if needs_sync {
+        commands.spawn(UISyncNeeded);
     }
 }
 
-pub fn handle_ui_rebuild(
+/// Every part of a container panel that refers to the containers it shows
+#[derive(SystemParam)]
+pub struct PanelRetargetQueries<'w, 's> {
+    children: Query<'w, 's, &'static Children>,
+    slots: Query<'w, 's, &'static mut InventorySlot>,
+    titles: Query<'w, 's, &'static mut Text, With<ContainerTitle>>,
+    sort_buttons: Query<'w, 's, &'static mut SortButton>,
+    furnace_bars: Query<'w, 's, &'static mut FurnaceProgressBar>,
+    scroll_views: Query<'w, 's, &'static mut ScrollView>,
+}
+
+impl<'w, 's> PanelRetargetQueries<'w, 's> {
+    /// Points a panel built for `from` at the containers of `to`, which must have the same shape
+    fn retarget(&mut self, panel: Entity, from: &ContainerLayout, to: &ContainerLayout) {
+        let grid_positions: HashMap<(ContainerType, usize), usize> = (0..from.rows * from.columns)
+            .filter_map(|position| from.slot_at(position).map(|slot| (slot, position)))
+            .collect();
+
+        for entity in self.children.iter_descendants(panel) {
+            if let Ok(mut slot) = self.slots.get_mut(entity) {
+                if let Some((container_type, index)) = grid_positions.get(&(slot.container_type.clone(), slot.index)).and_then(|&position| to.slot_at(position)) {
+                    slot.container_type = container_type;
+                    slot.index = index;
+                }
+            }
+            if let Ok(mut text) = self.titles.get_mut(entity) {
+                if let Some(section) = text.sections.first_mut() {
+                    section.value = to.title.clone();
+                }
+            }
+            if let Ok(mut sort_button) = self.sort_buttons.get_mut(entity) {
+                sort_button.container_type = to.container_type.clone();
+            }
+            if let (Ok(mut bar), ContainerType::Furnace(furnace_id)) = (self.furnace_bars.get_mut(entity), &to.container_type) {
+                bar.furnace_id = *furnace_id;
+            }
+            if let Ok(mut scroll_view) = self.scroll_views.get_mut(entity) {
+                scroll_view.first_row = 0;
+            }
+        }
+    }
+}
+
+/// Checks if a panel built for one layout looks exactly like one built for the other
+fn same_shape(a: &ContainerLayout, b: &ContainerLayout) -> bool {
+    let is_furnace = |layout: &ContainerLayout| matches!(layout.container_type, ContainerType::Furnace(_));
+
+    a.rows == b.rows
+        && a.columns == b.columns
+        && a.position == b.position
+        && a.title.is_empty() == b.title.is_empty()
+        && a.container_type.is_sortable() == b.container_type.is_sortable()
+        && is_furnace(a) == is_furnace(b)
+        && (0..a.rows * a.columns).all(|position| a.slot_at(position).is_some() == b.slot_at(position).is_some())
+}
+
+/// Brings the container panels and chest buttons in line with the `ContainerManager`
+///
+/// Panels are spawned once per layout and hidden when it closes, so reopening it shows the same
+/// entities again. A layout without a panel of its own, like another chest after a
+/// `SwitchChestEvent`, takes over a hidden panel of the same shape by pointing its slots at the
+/// new containers. Slot entities, and with them their hover state, survive all of this.
+pub fn sync_container_ui(
     mut commands: Commands,
     asset_server: Res<AssetServer>,
     container_manager: Res<ContainerManager>,
-    ui_query: Query<Entity, With<ContainerUI>>,
-    rebuild_query: Query<Entity, With<UIRebuildNeeded>>,
+    sync_query: Query<Entity, With<UISyncNeeded>>,
+    new_sections: Query<(), Added<ContainerSection>>,
+    section_query: Query<(Entity, &ContainerSection)>,
+    mut panel_query: Query<(Entity, &mut ContainerUI, &mut Style)>,
+    mut retarget_queries: PanelRetargetQueries,
+    chest_panel_query: Query<(Entity, Option<&Children>), With<ChestSelectionPanel>>,
+    mut chest_button_query: Query<(&ChestButton, &mut BackgroundColor)>,
 ) {
-    if !rebuild_query.is_empty() {
-        for entity in rebuild_query.iter() {
-            commands.entity(entity).despawn();
+    if sync_query.is_empty() && new_sections.is_empty() {
+        return;
+    }
+
+    for entity in sync_query.iter() {
+        commands.entity(entity).despawn();
+    }
+
+    // panels showing a layout that is still open keep it
+    let mut unused: Vec<(Entity, ContainerLayout)> = panel_query.iter()
+        .map(|(entity, container_ui, _)| (entity, container_ui.layout.clone()))
+        .collect();
+    let mut shown: Vec<Option<Entity>> = container_manager.layouts.iter()
+        .map(|layout| {
+            let position = unused.iter().position(|(_, panel_layout)| panel_layout == layout)?;
+            Some(unused.remove(position).0)
+        })
+        .collect();
+
+    // the rest reuse a hidden panel of the same shape or get a new one
+    for (layout, panel) in container_manager.layouts.iter().zip(shown.iter_mut()) {
+        if panel.is_some() {
+            continue;
+        }
+
+        *panel = Some(match unused.iter().position(|(_, panel_layout)| same_shape(panel_layout, layout)) {
+            Some(position) => {
+                let (entity, previous_layout) = unused.remove(position);
+                retarget_queries.retarget(entity, &previous_layout, layout);
+                if let Ok((_, mut container_ui, _)) = panel_query.get_mut(entity) {
+                    container_ui.layout = layout.clone();
+                }
+                entity
+            }
+            None => create_container_ui(&mut commands, &asset_server, layout),
+        });
+    }
+
+    for (entity, _, mut style) in &mut panel_query {
+        let display = if shown.contains(&Some(entity)) { Display::Flex } else { Display::None };
+        if style.display != display {
+            style.display = display;
         }
+    }
+
+    // shown panels in layout order, hidden ones after them
+    for (section, ContainerSection { position }) in &section_query {
+        let panels: Vec<Entity> = container_manager.layouts.iter()
+            .zip(&shown)
+            .filter(|(layout, _)| layout.position == *position)
+            .filter_map(|(_, panel)| *panel)
+            .chain(unused.iter().filter(|(_, layout)| layout.position == *position).map(|(entity, _)| *entity))
+            .collect();
+        commands.entity(section).replace_children(&panels);
+    }
 
-        for entity in ui_query.iter() {
-            commands.entity(entity).despawn_recursive();
+    sync_chest_buttons(&mut commands, &asset_server, &container_manager, &chest_panel_query, &mut chest_button_query);
+}
+
+/// Recolors the chest buttons for the active chest, respawning them only if the chests changed
+fn sync_chest_buttons(
+    commands: &mut Commands,
+    asset_server: &Res<AssetServer>,
+    container_manager: &ContainerManager,
+    chest_panel_query: &Query<(Entity, Option<&Children>), With<ChestSelectionPanel>>,
+    chest_button_query: &mut Query<(&ChestButton, &mut BackgroundColor)>,
+) {
+    let Ok((panel, children)) = chest_panel_query.get_single() else { return; };
+
+    let buttons: Vec<(Entity, u32)> = children.into_iter()
+        .flatten()
+        .filter_map(|&child| chest_button_query.get(child).ok().map(|(button, _)| (child, button.chest_id)))
+        .collect();
+
+    if buttons.iter().map(|&(_, chest_id)| chest_id).eq(container_manager.available_chests.iter().copied()) {
+        for &(entity, chest_id) in &buttons {
+            if let Ok((_, mut background_color)) = chest_button_query.get_mut(entity) {
+                let color = chest_button_color(container_manager.active_chest_id == Some(chest_id));
+                if background_color.0 != color {
+                    background_color.0 = color;
+                }
+            }
         }
+        return;
+    }
 
-        create_minecraft_ui(&mut commands, &asset_server, &container_manager);
+    for (entity, _) in buttons {
+        commands.entity(entity).despawn_recursive();
     }
-}
\ No newline at end of file
+    commands.entity(panel).with_children(|parent| {
+        for &chest_id in &container_manager.available_chests {
+            create_chest_button(parent, asset_server, chest_id, container_manager.active_chest_id == Some(chest_id));
+        }
+    });
+}
@@ -1,4 +1,6 @@
-use crate::world::inventory::components::{HeldItem, HeldItemDisplay, SelectedHotbarSlot, SelectedItemDisplay, InventorySlot, DragState};
+use crate::world::inventory::components::{HeldItem, HeldItemDisplay, SelectedHotbarSlot, SelectedItemDisplay, InventorySlot, DragState, FurnaceProgressBar, FurnaceProgressKind, ItemUseProgressBar};
+use crate::world::inventory::consumable::ItemUseState;
+use crate::world::inventory::furnace::Furnaces;
 use crate::world::inventory::containers::{ContainerManager, ContainerType};
 use crate::world::inventory::systems::format_item_display;
 use crate::systems::ui::slot_utils::clear_text;
@@ -36,11 +38,11 @@ pub fn update_held_item_display(
                         stack.size
                     };
                     
-                    if let Some(item) = stack.item {
+                    if stack.item.is_some() {
                         if display_count > 1 {
-                            text.sections[0].value = format!("{} ({})", item.display_name, display_count);
+                            text.sections[0].value = format!("{} ({})", stack.display_name(), display_count);
                         } else if display_count == 1 {
-                            text.sections[0].value = item.display_name.to_string();
+                            text.sections[0].value = stack.display_name().to_string();
                         } else {
                             clear_text(*text_entity, &mut text_query);
                         }
@@ -98,4 +100,37 @@ pub fn update_selected_item_display(
             }
         }
     }
-}
\ No newline at end of file
+}
+
+/// Resizes the fuel and smelting bars of open furnaces
+pub fn update_furnace_progress_bars(
+    furnaces: Res<Furnaces>,
+    mut bar_query: Query<(&mut Style, &FurnaceProgressBar)>,
+) {
+    for (mut style, bar) in &mut bar_query {
+        let fraction = furnaces.get(bar.furnace_id)
+            .map(|state| match bar.kind {
+                FurnaceProgressKind::Burn => state.burn_fraction(),
+                FurnaceProgressKind::Cook => state.cook_fraction(),
+            })
+            .unwrap_or(0.0);
+
+        let width = Val::Percent(fraction * 100.0);
+        if style.width != width {
+            style.width = width;
+        }
+    }
+}
+
+/// Resizes the use progress bar under the selected item display
+pub fn update_item_use_progress_bar(
+    use_state: Res<ItemUseState>,
+    mut bar_query: Query<&mut Style, With<ItemUseProgressBar>>,
+) {
+    let width = Val::Percent(use_state.fraction() * 100.0);
+    for mut style in &mut bar_query {
+        if style.width != width {
+            style.width = width;
+        }
+    }
+}
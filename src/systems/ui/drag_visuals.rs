@@ -45,13 +45,19 @@ impl SlotDragContext {
 }
 
 /// Determines the appropriate border color for a slot based on its state
+///
+/// `rejects_held` is set when the slot's filter doesn't accept the held item at all.
 pub fn determine_slot_border_color(
     is_selected: bool,
     drag_context: &SlotDragContext,
     preview_count: u32,
+    rejects_held: bool,
 ) -> Color {
     if is_selected {
         Color::rgb(1.0, 1.0, 0.0) // Yellow for selected (highest priority)
+    } else if rejects_held && drag_context.is_dragging() && drag_context.show_drag_highlighting
+        && (drag_context.is_currently_hovered || drag_context.is_left_drag_target || drag_context.is_right_drag_target) {
+        Color::rgb(0.8, 0.1, 0.1) // Red for slots that can't hold the dragged item
     } else if drag_context.is_currently_hovered && drag_context.is_dragging() && drag_context.show_drag_highlighting {
         if preview_count > 0 {
             if drag_context.is_right_dragging {
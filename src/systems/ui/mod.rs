@@ -4,7 +4,9 @@ pub mod slot_utils;
 pub mod drag_visuals;
 pub mod display_systems;
 pub mod slot_rendering;
+pub mod scroll;
+pub mod tooltip;
 
 // Re-export public functions to maintain API compatibility
 pub use slot_rendering::update_slot_visuals;
-pub use display_systems::{update_held_item_display, update_slot_hover_effects, update_selected_item_display};
\ No newline at end of file
+pub use display_systems::{update_held_item_display, update_slot_hover_effects, update_selected_item_display, update_furnace_progress_bars, update_item_use_progress_bar};
\ No newline at end of file
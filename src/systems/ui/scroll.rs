@@ -0,0 +1,67 @@
+use crate::world::inventory::components::{ScrollRow, ScrollView, SLOT_MARGIN, SLOT_SIZE};
+use crate::world::inventory::containers::ContainerPosition;
+use crate::world::inventory::ui::ContainerSection;
+use bevy::input::mouse::MouseWheel;
+use bevy::prelude::*;
+
+const ROW_HEIGHT: f32 = SLOT_SIZE + SLOT_MARGIN * 2.0;
+
+/// Shows as many rows of each `ScrollView` as fit in the window, the mouse wheel scrolls the hovered one
+///
+/// The room for rows is measured on the last layout: the rows shown now plus whatever space is left
+/// above the top containers, which is negative once the panels overflow the window.
+pub fn scroll_container_views(
+    mut scroll_events: EventReader<MouseWheel>,
+    windows: Query<&Window>,
+    section_query: Query<(&ContainerSection, &GlobalTransform, &Node)>,
+    mut view_query: Query<(&mut ScrollView, &mut Style, &GlobalTransform, &Node)>,
+    mut row_query: Query<(&ScrollRow, &mut Style), Without<ScrollView>>,
+) {
+    let Ok(window) = windows.get_single() else { return; };
+
+    // the middle section grows to fill the window and packs its content at the bottom
+    let free_height = section_query.iter()
+        .find(|(section, ..)| section.position == ContainerPosition::Top)
+        .map_or(0.0, |(_, transform, node)| transform.translation().y - node.size().y / 2.0);
+    let shown_height = view_query.iter()
+        .filter(|(.., node)| node.size() != Vec2::ZERO) // views of hidden panels take no room
+        .map(|(view, ..)| view.visible_rows as f32 * ROW_HEIGHT)
+        .fold(0.0, f32::max);
+    let max_rows = ((free_height + shown_height) / ROW_HEIGHT).floor().max(1.0) as usize;
+    let scroll: f32 = scroll_events.read().map(|event| event.y).sum();
+
+    for (mut view, mut style, transform, node) in &mut view_query {
+        let visible_rows = view.rows.min(max_rows);
+        let mut first_row = view.first_row;
+
+        let is_hovered = window.cursor_position()
+            .is_some_and(|cursor_pos| Rect::from_center_size(transform.translation().truncate(), node.size()).contains(cursor_pos));
+        if is_hovered && scroll != 0.0 {
+            first_row = if scroll > 0.0 { first_row.saturating_sub(1) } else { first_row + 1 };
+        }
+        first_row = first_row.min(view.rows - visible_rows);
+
+        if view.first_row != first_row || view.visible_rows != visible_rows {
+            view.first_row = first_row;
+            view.visible_rows = visible_rows;
+        }
+
+        let height = Val::Px(visible_rows as f32 * ROW_HEIGHT + 20.0);
+        if style.height != height {
+            style.height = height;
+        }
+    }
+
+    for (scroll_row, mut style) in &mut row_query {
+        let Ok((view, ..)) = view_query.get(scroll_row.view) else { continue; };
+
+        let display = if (view.first_row..view.first_row + view.visible_rows).contains(&scroll_row.row) {
+            Display::Flex
+        } else {
+            Display::None
+        };
+        if style.display != display {
+            style.display = display;
+        }
+    }
+}
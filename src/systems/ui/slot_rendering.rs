@@ -1,12 +1,18 @@
-use crate::systems::ui::container::UIRebuildNeeded;
-use crate::world::inventory::components::{HeldItem, InventorySlot, SelectedHotbarSlot, ItemSprite, ItemCountText, DragState, SlotPreviewText};
+use crate::systems::ui::container::UISyncNeeded;
+use crate::world::inventory::components::{HeldItem, InventorySlot, SelectedHotbarSlot, ItemSprite, ItemCountText, DragState, SlotPreviewText, SlotDurabilityBar, SlotDurabilityFill, SlotDamageOverlay, SlotRenderState};
 use crate::world::inventory::containers::{ContainerManager, ContainerType, ContainerUI};
+use crate::world::inventory::item_stack::ItemStack;
 use crate::world::inventory::ui::ItemSpritesheet;
-use crate::systems::ui::slot_utils::{sprite_coords_to_atlas_index, clear_text, can_slot_accept_items};
+use crate::systems::ui::slot_utils::{sprite_coords_to_atlas_index, clear_text, can_slot_accept_items, durability_color};
 use crate::systems::ui::drag_visuals::{SlotDragContext, determine_slot_border_color, calculate_drag_preview};
+use bevy::ecs::system::SystemParam;
 use bevy::hierarchy::Children;
-use bevy::prelude::{BackgroundColor, Color, Entity, Query, Res, With, Without, UiImage, Visibility, TextureAtlas};
-use bevy::ui::BorderColor;
+use bevy::prelude::{BackgroundColor, Changed, Color, DetectChanges, Entity, Local, Or, Query, Ref, RemovedComponents, Res, With, Without, UiImage, Vec2, Visibility, TextureAtlas};
+use bevy::ui::{BorderColor, Display, Node, Style, Val};
+use std::collections::{HashMap, HashSet};
+
+/// Remaining durability fraction at or below which the cracked overlay is drawn
+pub const CRACKED_BELOW: f32 = 0.1;
 
 /// Container for slot child entities to avoid repeated traversal
 #[derive(Default)]
@@ -14,6 +20,52 @@ pub struct SlotChildren {
     pub sprite: Option<Entity>,
     pub count_text: Option<Entity>,
     pub preview_text: Option<Entity>,
+    pub durability_bar: Option<Entity>,
+    pub damage_overlay: Option<Entity>,
+}
+
+/// Fill nodes of slot durability bars, kept apart from the slots' own `BackgroundColor`
+type DurabilityFillFilter = (With<SlotDurabilityFill>, Without<SlotDurabilityBar>, Without<InventorySlot>);
+
+/// The durability bar and damage overlay of every slot, bundled to keep `update_slot_visuals` manageable
+#[derive(SystemParam)]
+pub struct SlotDurabilityQueries<'w, 's> {
+    bars: Query<'w, 's, (&'static mut SlotDurabilityBar, &'static mut Style, &'static Children)>,
+    fills: Query<'w, 's, (&'static mut Style, &'static mut BackgroundColor), DurabilityFillFilter>,
+    overlays: Query<'w, 's, &'static mut Visibility, (With<SlotDamageOverlay>, Without<ItemSprite>)>,
+}
+
+impl SlotDurabilityQueries<'_, '_> {
+
+    /// Redraws a slot's durability bar and damage overlay, only if its durability changed since the last time
+    fn update(&mut self, slot_children: &SlotChildren, stack: Option<&ItemStack>) {
+        let Some(bar_entity) = slot_children.durability_bar else { return; };
+        let Ok((mut bar, mut bar_style, bar_children)) = self.bars.get_mut(bar_entity) else { return; };
+
+        let durability = stack.and_then(|stack| stack.durability.zip(stack.max_durability()));
+        if bar.shown == durability {
+            return;
+        }
+        bar.shown = durability;
+
+        let fraction = stack.and_then(ItemStack::durability_fraction);
+        bar_style.display = if fraction.is_some() { Display::Flex } else { Display::None };
+
+        if let Some(fraction) = fraction {
+            for &child in bar_children.iter() {
+                if let Ok((mut fill_style, mut fill_color)) = self.fills.get_mut(child) {
+                    fill_style.width = Val::Percent(fraction * 100.0);
+                    *fill_color = durability_color(fraction).into();
+                }
+            }
+        }
+
+        if let Some(overlay_entity) = slot_children.damage_overlay {
+            if let Ok(mut visibility) = self.overlays.get_mut(overlay_entity) {
+                *visibility = if fraction.is_some_and(|fraction| fraction <= CRACKED_BELOW) { Visibility::Inherited } else { Visibility::Hidden };
+            }
+        }
+    }
 }
 
 impl SlotChildren {
@@ -23,6 +75,7 @@ impl SlotChildren {
         sprite_query: &Query<(&mut UiImage, &mut TextureAtlas, &mut Visibility), With<ItemSprite>>,
         text_query: &Query<&mut bevy::prelude::Text, With<ItemCountText>>,
         preview_text_query: &Query<&mut bevy::prelude::Text, (With<SlotPreviewText>, Without<ItemCountText>)>,
+        durability_queries: &SlotDurabilityQueries,
     ) -> Self {
         let mut children = Self::default();
         
@@ -37,6 +90,12 @@ impl SlotChildren {
                 if preview_text_query.get(grandchild).is_ok() {
                     children.preview_text = Some(grandchild);
                 }
+                if durability_queries.bars.get(grandchild).is_ok() {
+                    children.durability_bar = Some(grandchild);
+                }
+                if durability_queries.overlays.get(grandchild).is_ok() {
+                    children.damage_overlay = Some(grandchild);
+                }
             }
         }
         
@@ -44,32 +103,172 @@ impl SlotChildren {
     }
 }
 
+/// Everything besides a slot's own contents that its border and drag preview depend on
+#[derive(PartialEq)]
+struct SlotViewInputs {
+    selected_hotbar_slot: usize,
+    held: Option<ItemStack>,
+    is_left_dragging: bool,
+    is_right_dragging: bool,
+    left_drag_slots: Vec<(ContainerType, usize)>,
+    right_drag_slots: Vec<(ContainerType, usize)>,
+    hovered_slot: Option<(ContainerType, usize)>,
+}
+
+impl SlotViewInputs {
+    fn new(selected_hotbar_slot: &SelectedHotbarSlot, held_item: &HeldItem, drag_state: &DragState) -> Self {
+        Self {
+            selected_hotbar_slot: selected_hotbar_slot.slot_index,
+            held: held_item.stack.clone(),
+            is_left_dragging: drag_state.is_left_dragging,
+            is_right_dragging: drag_state.is_right_dragging,
+            left_drag_slots: drag_state.left_drag_slots.clone(),
+            right_drag_slots: drag_state.right_drag_slots.clone(),
+            hovered_slot: drag_state.current_hovered_slot.clone(),
+        }
+    }
+}
+
+/// What `update_slot_visuals` saw when it last ran
+#[derive(Default)]
+pub struct SlotRenderCache {
+    revisions: HashMap<ContainerType, u64>, // `SlotContainer::revision` of every container
+    view_inputs: Option<SlotViewInputs>,
+    slot_entities: HashMap<(ContainerType, usize), Vec<Entity>>, // the slot entities showing each container slot
+    slot_keys: HashMap<Entity, (ContainerType, usize)>, // the other way around
+    pending: HashSet<Entity>, // retargeted, shown or resized since the last redraw
+}
+
+impl SlotRenderCache {
+    fn track(&mut self, entity: Entity, slot: &InventorySlot) {
+        self.untrack(entity);
+
+        let key = (slot.container_type.clone(), slot.index);
+        self.slot_entities.entry(key.clone()).or_default().push(entity);
+        self.slot_keys.insert(entity, key);
+    }
+
+    fn untrack(&mut self, entity: Entity) {
+        let Some(key) = self.slot_keys.remove(&entity) else { return; };
+
+        if let Some(entities) = self.slot_entities.get_mut(&key) {
+            entities.retain(|&tracked| tracked != entity);
+            if entities.is_empty() {
+                self.slot_entities.remove(&key);
+            }
+        }
+    }
+
+    /// Entities of the slots written since the last redraw, found through the slots' revisions
+    fn written_slots(&self, container_manager: &ContainerManager) -> Vec<Entity> {
+        let mut entities = Vec::new();
+
+        for (container_type, container) in &container_manager.containers {
+            let seen = self.revisions.get(container_type).copied();
+            if seen == Some(container.revision()) {
+                continue;
+            }
+
+            for index in 0..container.len() {
+                if seen.is_some_and(|seen| container.slot_revision(index) <= seen) {
+                    continue;
+                }
+                if let Some(slot_entities) = self.slot_entities.get(&(container_type.clone(), index)) {
+                    entities.extend(slot_entities);
+                }
+            }
+        }
+
+        entities
+    }
+}
+
+/// A slot entity and everything `update_slot_visuals` draws on it directly
+type SlotVisuals = (Entity, Ref<'static, InventorySlot>, &'static Node, &'static Children, &'static mut SlotRenderState, &'static mut BackgroundColor, &'static mut BorderColor);
+
+/// Slot entities that were pointed at another slot, or whose panel was shown or resized
+type RetargetedSlotFilter = (With<InventorySlot>, Or<(Changed<InventorySlot>, Changed<Node>)>);
+
 /// Main system for updating slot visuals including sprites, text, borders, and drag previews
+///
+/// Only slots that changed are redrawn: the slots written since the last run are looked up through
+/// their revisions and a map from container slot to slot entities, plus slot entities that were
+/// retargeted or shown again. Borders and drag previews of every visible slot are redrawn when the
+/// selection, held item or drag changed, hidden panels wait until they are shown. Frames where none
+/// of that happened cost one revision check per container.
 pub fn update_slot_visuals(
     container_manager: Res<ContainerManager>,
     selected_hotbar_slot: Res<SelectedHotbarSlot>,
     drag_state: Res<DragState>,
     held_item: Res<HeldItem>,
     spritesheet: Res<ItemSpritesheet>,
-    mut slot_query: Query<(&InventorySlot, &Children, &mut BackgroundColor, &mut BorderColor)>,
+    mut slot_query: Query<SlotVisuals>,
     mut sprite_query: Query<(&mut UiImage, &mut TextureAtlas, &mut Visibility), With<ItemSprite>>,
     mut text_query: Query<&mut bevy::prelude::Text, With<ItemCountText>>,
     mut preview_text_query: Query<&mut bevy::prelude::Text, (With<SlotPreviewText>, Without<ItemCountText>)>,
     children_query: Query<&Children, Without<InventorySlot>>,
+    mut durability_queries: SlotDurabilityQueries,
+    changed_slots: Query<(Entity, Ref<InventorySlot>), RetargetedSlotFilter>,
+    mut removed_slots: RemovedComponents<InventorySlot>,
+    mut cache: Local<SlotRenderCache>,
     ui_query: Query<Entity, With<ContainerUI>>,
-    rebuild_query: Query<Entity, With<UIRebuildNeeded>>,
+    rebuild_query: Query<Entity, With<UISyncNeeded>>,
 ) {
+    // keep the slot map up to date even in frames that don't redraw
+    for entity in removed_slots.read() {
+        cache.untrack(entity);
+        cache.pending.remove(&entity);
+    }
+    for (entity, slot) in &changed_slots {
+        if slot.is_changed() {
+            cache.track(entity, &slot);
+        }
+        cache.pending.insert(entity);
+    }
+
     if !rebuild_query.is_empty() || ui_query.is_empty() {
         return;
     }
 
-    for (slot, children, mut bg_color, mut border_color) in &mut slot_query {
-        if let Some(container) = container_manager.get_container(&slot.container_type) {
-            let is_selected = is_slot_selected(slot, &selected_hotbar_slot);
-            let slot_children = find_slot_children(children, &children_query, &sprite_query, &text_query, &preview_text_query);
-            
+    // resources are often written without changing, so compare what the borders depend on
+    let mut view_changed = false;
+    if selected_hotbar_slot.is_changed() || held_item.is_changed() || drag_state.is_changed() {
+        let view_inputs = SlotViewInputs::new(&selected_hotbar_slot, &held_item, &drag_state);
+        if cache.view_inputs.as_ref() != Some(&view_inputs) {
+            cache.view_inputs = Some(view_inputs);
+            view_changed = true;
+        }
+    }
+
+    let contents_changed = container_manager.containers.len() != cache.revisions.len()
+        || container_manager.containers.iter().any(|(container_type, container)| cache.revisions.get(container_type) != Some(&container.revision()));
+
+    if !view_changed && !contents_changed && cache.pending.is_empty() {
+        return;
+    }
+
+    let mut targets = std::mem::take(&mut cache.pending);
+    if contents_changed {
+        targets.extend(cache.written_slots(&container_manager));
+    }
+    if view_changed {
+        targets.extend(slot_query.iter()
+            .filter(|(_, _, node, ..)| node.size() != Vec2::ZERO)
+            .map(|(entity, ..)| entity));
+    }
+
+    for entity in targets {
+        let Ok((_, slot, _, children, mut render_state, mut bg_color, mut border_color)) = slot_query.get_mut(entity) else { continue; };
+        let Some(container) = container_manager.get_container(&slot.container_type) else { continue; };
+
+        let revision = container.slot_revision(slot.index);
+        let is_dirty = slot.is_changed() || render_state.revision != Some(revision);
+
+        let slot_children = find_slot_children(children, &children_query, &sprite_query, &text_query, &preview_text_query, &durability_queries);
+
+        if is_dirty {
             update_slot_content_visuals(
-                slot,
+                &slot,
                 container,
                 &slot_children,
                 &spritesheet,
@@ -77,20 +276,35 @@ pub fn update_slot_visuals(
                 &mut text_query,
                 &mut bg_color,
             );
+            durability_queries.update(&slot_children, container.get_slot(slot.index));
+            render_state.revision = Some(revision);
+        }
 
-            let drag_context = SlotDragContext::new(slot, &drag_state);
-            let preview_count = calculate_slot_preview_count(&drag_context, &held_item, &container_manager, slot);
-            
+        let is_selected = is_slot_selected(&slot, &selected_hotbar_slot);
+        let drag_context = SlotDragContext::new(&slot, &drag_state);
+        let preview_count = calculate_slot_preview_count(&drag_context, &held_item, &container_manager, &slot);
+
+        let preview = Some((preview_count, drag_context.is_right_dragging));
+        if render_state.preview != preview {
             update_slot_preview_text(
                 &slot_children,
                 preview_count,
                 &drag_context,
                 &mut preview_text_query,
             );
-            
-            *border_color = determine_slot_border_color(is_selected, &drag_context, preview_count).into();
+            render_state.preview = preview;
+        }
+
+        let rejects_held = held_item.stack.as_ref().is_some_and(|held_stack| !container.can_insert(slot.index, held_stack));
+        let border = determine_slot_border_color(is_selected, &drag_context, preview_count, rejects_held);
+        if border_color.0 != border {
+            border_color.0 = border;
         }
     }
+
+    cache.revisions = container_manager.containers.iter()
+        .map(|(container_type, container)| (container_type.clone(), container.revision()))
+        .collect();
 }
 
 fn is_slot_selected(slot: &InventorySlot, selected_hotbar_slot: &SelectedHotbarSlot) -> bool {
@@ -103,6 +317,7 @@ fn find_slot_children(
     sprite_query: &Query<(&mut UiImage, &mut TextureAtlas, &mut Visibility), With<ItemSprite>>,
     text_query: &Query<&mut bevy::prelude::Text, With<ItemCountText>>,
     preview_text_query: &Query<&mut bevy::prelude::Text, (With<SlotPreviewText>, Without<ItemCountText>)>,
+    durability_queries: &SlotDurabilityQueries,
 ) -> SlotChildren {
     if let Some(&container_child) = children.first() {
         SlotChildren::find_from_slot_children(
@@ -111,6 +326,7 @@ fn find_slot_children(
             sprite_query,
             text_query,
             preview_text_query,
+            durability_queries,
         )
     } else {
         SlotChildren::default()
@@ -137,6 +353,10 @@ fn update_slot_content_visuals(
         clear_slot_count_text(slot_children, text_query);
         *bg_color = Color::rgb(0.4, 0.4, 0.4).into();
     }
+
+    if container.is_slot_locked(slot.index) {
+        *bg_color = Color::rgb(0.6, 0.5, 0.2).into(); // Amber for slots sorting skips
+    }
 }
 
 fn update_slot_sprite(
@@ -1,10 +1,17 @@
 use crate::world::inventory::containers::{ContainerManager, ContainerType};
 use crate::world::inventory::item_stack::ItemStack;
-use bevy::prelude::{Entity, Query, Text};
+use crate::world::item::definitions::SPRITESHEET_COLUMNS;
+use bevy::prelude::{Color, Entity, Query, Text};
 
 /// Converts sprite coordinates (x, y) to atlas index for an 8x9 spritesheet
 pub fn sprite_coords_to_atlas_index(sprite_x: u8, sprite_y: u8) -> usize {
-    (sprite_y as usize * 8) + sprite_x as usize
+    (sprite_y as usize * SPRITESHEET_COLUMNS as usize) + sprite_x as usize
+}
+
+/// Color of a durability bar, from green when new to red when about to break
+pub fn durability_color(fraction: f32) -> Color {
+    let fraction = fraction.clamp(0.0, 1.0);
+    Color::rgb(1.0 - fraction, fraction, 0.2)
 }
 
 /// Check if a slot can accept items from a held stack
@@ -15,6 +22,10 @@ pub fn can_slot_accept_items(
     container_manager: &ContainerManager,
 ) -> bool {
     if let Some(container) = container_manager.get_container(container_type) {
+        if !container.can_insert(slot_index, held_stack) {
+            return false;
+        }
+
         match container.get_slot(slot_index) {
             None => true, // Empty slot can accept items
             Some(existing_stack) => {
@@ -35,6 +46,10 @@ pub fn calculate_available_space(
     container_manager: &ContainerManager,
 ) -> u32 {
     if let Some(container) = container_manager.get_container(container_type) {
+        if !container.can_insert(slot_index, held_stack) {
+            return 0;
+        }
+
         match container.get_slot(slot_index) {
             None => held_stack.size, // Empty slot can take the whole stack
             Some(existing_stack) => {
@@ -0,0 +1,189 @@
+use crate::systems::ui::slot_utils::durability_color;
+use crate::utils::slot_finder::find_slot_under_cursor;
+use crate::world::inventory::components::{DebugMode, DragState, HeldItem, InventorySlot, ItemTooltip, ItemTooltipText, TooltipDurabilityBar, TooltipDurabilityFill};
+use crate::world::inventory::containers::ContainerManager;
+use crate::world::inventory::item_stack::ItemStack;
+use bevy::prelude::*;
+
+/// Distance between the cursor and the tooltip's closest corner
+const CURSOR_OFFSET: f32 = 14.0;
+
+const NAME_FONT_SIZE: f32 = 18.0;
+const DETAIL_FONT_SIZE: f32 = 14.0;
+
+const NAME_COLOR: Color = Color::WHITE;
+const CUSTOM_NAME_COLOR: Color = Color::rgb(1.0, 0.85, 0.3);
+const IDENTIFIER_COLOR: Color = Color::rgb(0.5, 0.5, 0.5);
+const ENCHANTMENT_COLOR: Color = Color::rgb(0.7, 0.6, 1.0);
+const LORE_COLOR: Color = Color::rgb(0.6, 0.5, 0.8);
+const DETAIL_COLOR: Color = Color::rgb(0.75, 0.75, 0.75);
+
+type DurabilityBarQuery<'w, 's> = Query<'w, 's, &'static mut Style, (With<TooltipDurabilityBar>, Without<ItemTooltip>)>;
+type DurabilityFillQuery<'w, 's> = Query<'w, 's, (&'static mut Style, &'static mut BackgroundColor), (With<TooltipDurabilityFill>, Without<ItemTooltip>, Without<TooltipDurabilityBar>)>;
+
+/// One line of the tooltip: text, font size and color
+struct TooltipLine {
+    text: String,
+    font_size: f32,
+    color: Color,
+}
+
+impl TooltipLine {
+    fn detail(text: impl Into<String>, color: Color) -> Self {
+        Self { text: text.into(), font_size: DETAIL_FONT_SIZE, color }
+    }
+}
+
+/// Shows the details of the stack under the cursor, hidden while holding or dragging items
+///
+/// The tooltip sits below and to the right of the cursor, flipping to the other side of it
+/// wherever it would leave the window.
+pub fn update_item_tooltip(
+    container_manager: Res<ContainerManager>,
+    held_item: Res<HeldItem>,
+    drag_state: Res<DragState>,
+    debug_mode: Res<DebugMode>,
+    windows: Query<&Window>,
+    slot_query: Query<(&InventorySlot, &GlobalTransform, &Node)>,
+    mut tooltip_query: Query<(&mut Style, &Node), With<ItemTooltip>>,
+    mut text_query: Query<&mut Text, With<ItemTooltipText>>,
+    mut bar_query: DurabilityBarQuery,
+    mut fill_query: DurabilityFillQuery,
+) {
+    let Ok((mut style, node)) = tooltip_query.get_single_mut() else { return; };
+
+    let is_dragging = drag_state.is_left_dragging || drag_state.is_right_dragging || drag_state.is_shift_dragging;
+    let window = windows.get_single().ok();
+    let cursor_pos = window.and_then(Window::cursor_position);
+
+    let hovered = cursor_pos
+        .filter(|_| !is_dragging && held_item.stack.is_none())
+        .and_then(|cursor_pos| find_slot_under_cursor(cursor_pos, &slot_query))
+        .and_then(|slot| container_manager.get_container(&slot.container_type)?.get_slot(slot.index));
+
+    let (Some(stack), Some(window), Some(cursor_pos)) = (hovered, window, cursor_pos) else {
+        if style.display != Display::None {
+            style.display = Display::None;
+        }
+        return;
+    };
+
+    if style.display != Display::Flex {
+        style.display = Display::Flex;
+    }
+
+    if let Ok(mut text) = text_query.get_single_mut() {
+        set_tooltip_text(&mut text, tooltip_lines(stack, debug_mode.enabled));
+    }
+
+    let durability_fraction = stack.durability_fraction();
+
+    for mut bar_style in &mut bar_query {
+        let display = if durability_fraction.is_some() { Display::Flex } else { Display::None };
+        if bar_style.display != display {
+            bar_style.display = display;
+        }
+    }
+
+    if let Some(fraction) = durability_fraction {
+        for (mut fill_style, mut fill_color) in &mut fill_query {
+            let width = Val::Percent(fraction * 100.0);
+            if fill_style.width != width {
+                fill_style.width = width;
+            }
+            *fill_color = durability_color(fraction).into();
+        }
+    }
+
+    // the size is from the last layout, a tooltip that just changed may be off by a frame
+    let size = node.size();
+    let left = if cursor_pos.x + CURSOR_OFFSET + size.x > window.width() {
+        cursor_pos.x - CURSOR_OFFSET - size.x
+    } else {
+        cursor_pos.x + CURSOR_OFFSET
+    };
+    let top = if cursor_pos.y + CURSOR_OFFSET + size.y > window.height() {
+        cursor_pos.y - CURSOR_OFFSET - size.y
+    } else {
+        cursor_pos.y + CURSOR_OFFSET
+    };
+
+    style.left = Val::Px(left.max(0.0));
+    style.top = Val::Px(top.max(0.0));
+}
+
+/// Name, identifier (in debug mode), enchantments, lore, size, durability, flags and attributes
+fn tooltip_lines(stack: &ItemStack, show_identifier: bool) -> Vec<TooltipLine> {
+    let Some(item) = stack.item else { return Vec::new(); };
+    let metadata = stack.metadata.as_deref();
+
+    let has_custom_name = metadata.is_some_and(|metadata| metadata.custom_name.is_some());
+    let mut lines = vec![TooltipLine {
+        text: stack.display_name().to_string(),
+        font_size: NAME_FONT_SIZE,
+        color: if has_custom_name { CUSTOM_NAME_COLOR } else { NAME_COLOR },
+    }];
+
+    if show_identifier {
+        lines.push(TooltipLine::detail(item.identifier, IDENTIFIER_COLOR));
+    }
+
+    if let Some(metadata) = metadata {
+        for enchantment in &metadata.enchantments {
+            lines.push(TooltipLine::detail(format!("{} {}", enchantment.identifier, enchantment.level), ENCHANTMENT_COLOR));
+        }
+        for line in &metadata.lore {
+            lines.push(TooltipLine::detail(line.clone(), LORE_COLOR));
+        }
+    }
+
+    if item.properties.max_stack_size > 1 {
+        lines.push(TooltipLine::detail(format!("Stack: {} / {}", stack.size, item.properties.max_stack_size), DETAIL_COLOR));
+    }
+
+    if let (Some(durability), Some(max_durability)) = (stack.durability, stack.max_durability()) {
+        lines.push(TooltipLine::detail(format!("Durability: {} / {}", durability, max_durability), DETAIL_COLOR));
+    }
+
+    if item.properties.is_consumable {
+        lines.push(TooltipLine::detail("Consumable", DETAIL_COLOR));
+    }
+    if item.properties.offhand_equipable {
+        lines.push(TooltipLine::detail("Can be held in the offhand", DETAIL_COLOR));
+    }
+
+    if let Some(metadata) = metadata {
+        for (key, value) in &metadata.attributes {
+            lines.push(TooltipLine::detail(format!("{}: {}", key, value), IDENTIFIER_COLOR));
+        }
+    }
+
+    lines
+}
+
+/// Replaces the text's sections with `lines`, leaving it untouched if nothing changed
+fn set_tooltip_text(text: &mut Text, lines: Vec<TooltipLine>) {
+    let sections: Vec<(String, f32, Color)> = lines.into_iter()
+        .enumerate()
+        .map(|(index, line)| {
+            let value = if index == 0 { line.text } else { format!("\n{}", line.text) };
+            (value, line.font_size, line.color)
+        })
+        .collect();
+
+    let unchanged = text.sections.len() == sections.len()
+        && text.sections.iter().zip(&sections).all(|(section, (value, font_size, color))| {
+            section.value == *value && section.style.font_size == *font_size && section.style.color == *color
+        });
+    if unchanged {
+        return;
+    }
+
+    let font = text.sections.first().map(|section| section.style.font.clone()).unwrap_or_default();
+    text.sections = sections.into_iter()
+        .map(|(value, font_size, color)| TextSection {
+            value,
+            style: TextStyle { font: font.clone(), font_size, color },
+        })
+        .collect();
+}
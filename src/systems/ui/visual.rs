@@ -1,3 +1,3 @@
 // Re-export functions from modular files for backwards compatibility
 pub use crate::systems::ui::slot_rendering::update_slot_visuals;
-pub use crate::systems::ui::display_systems::{update_held_item_display, update_slot_hover_effects, update_selected_item_display};
\ No newline at end of file
+pub use crate::systems::ui::display_systems::{update_held_item_display, update_slot_hover_effects, update_selected_item_display, update_furnace_progress_bars, update_item_use_progress_bar};
\ No newline at end of file
@@ -0,0 +1,149 @@
+use crate::world::crafting::recipe::RecipeRegistry;
+use crate::world::inventory::components::HeldItem;
+use crate::world::inventory::containers::{ContainerManager, ContainerType, CraftingStation};
+use crate::world::inventory::inventory::SlotContainer;
+use crate::world::inventory::item_stack::ItemStack;
+use crate::world::inventory::routing::ShiftClickRouting;
+use crate::world::item::item::Item;
+use crate::world::item::registry::ItemRegistry;
+use std::ops::Range;
+
+/// Recomputes the output slot of a crafting station from the contents of its grid
+pub fn refresh_crafting_output(
+    station: CraftingStation,
+    container_manager: &mut ContainerManager,
+    recipes: &RecipeRegistry,
+    item_registry: &ItemRegistry,
+) {
+    let result = container_manager.get_container(&ContainerType::CraftingGrid(station))
+        .and_then(|grid| recipes.find_match(grid, station.grid_width()))
+        .and_then(|recipe| {
+            item_registry.get(recipe.result).map(|item| ItemStack::new(item, recipe.count))
+        });
+
+    let Some(output) = container_manager.get_container_mut(&ContainerType::CraftingOutput(station)) else {
+        return;
+    };
+
+    let unchanged = match (output.get_slot(0), &result) {
+        (Some(current), Some(result)) => current.item == result.item && current.size == result.size,
+        (None, None) => true,
+        _ => false,
+    };
+
+    if !unchanged {
+        let _ = output.set_slot(0, result);
+    }
+}
+
+/// Takes the crafted item from the output slot onto the cursor, consuming one of each ingredient
+///
+/// Returns the crafted stack. Does nothing if the cursor holds something that can't take the whole result.
+pub fn take_crafting_output(
+    station: CraftingStation,
+    container_manager: &mut ContainerManager,
+    held_item: &mut HeldItem,
+) -> Option<ItemStack> {
+    let result = container_manager.get_container(&ContainerType::CraftingOutput(station))
+        .and_then(|output| output.get_slot(0))
+        .cloned()?;
+
+    match &mut held_item.stack {
+        None => held_item.stack = Some(result.clone()),
+        Some(held_stack) => {
+            let max_size = held_stack.item.unwrap().properties.max_stack_size;
+            if !held_stack.can_merge_with(&result) || held_stack.size + result.size > max_size {
+                return None;
+            }
+            held_stack.size += result.size;
+        }
+    }
+
+    consume_crafting_ingredients(station, container_manager);
+    Some(result)
+}
+
+/// Shift-click on the output slot: crafts as many times as the ingredients allow,
+/// moving each result into the usual shift-click targets until they're full
+///
+/// Returns the crafted item and how many of it were made in total.
+pub fn craft_all_into_targets(
+    station: CraftingStation,
+    container_manager: &mut ContainerManager,
+    recipes: &RecipeRegistry,
+    item_registry: &ItemRegistry,
+    routing: &ShiftClickRouting,
+) -> Option<(Item, u32)> {
+    let output_type = ContainerType::CraftingOutput(station);
+    let targets = routing.targets(&output_type, container_manager);
+    let mut crafted: Option<(Item, u32)> = None;
+
+    loop {
+        refresh_crafting_output(station, container_manager, recipes, item_registry);
+
+        let Some(result) = container_manager.get_container(&output_type)
+            .and_then(|output| output.get_slot(0))
+            .cloned() else {
+            return crafted;
+        };
+
+        // only craft if the whole result fits, crafting never leaves partial results behind
+        let capacity: u32 = targets.iter()
+            .filter_map(|target| Some((container_manager.get_container(&target.container_type)?, target)))
+            .map(|(container, target)| available_capacity(container, &result, target.slots.clone().unwrap_or(0..container.len())))
+            .sum();
+
+        if capacity < result.size {
+            return crafted;
+        }
+
+        let mut remaining = Some(result.clone());
+        for target in &targets {
+            let Some(stack) = remaining.take() else { break; };
+            remaining = match container_manager.get_container_mut(&target.container_type) {
+                Some(container) => {
+                    let slots = target.slots.clone().unwrap_or(0..container.len());
+                    container.try_place_stack_in_slots(stack, slots)
+                }
+                None => Some(stack),
+            };
+        }
+
+        consume_crafting_ingredients(station, container_manager);
+
+        let item = result.item.unwrap();
+        crafted = Some((item, crafted.map_or(0, |(_, count)| count) + result.size));
+    }
+}
+
+/// Removes one item from every occupied grid slot and clears the output until it is recomputed
+fn consume_crafting_ingredients(station: CraftingStation, container_manager: &mut ContainerManager) {
+    if let Some(grid) = container_manager.get_container_mut(&ContainerType::CraftingGrid(station)) {
+        for slot_index in 0..grid.len() {
+            let Some(mut stack) = grid.get_slot_mut(slot_index) else { continue; };
+
+            stack.size -= 1;
+            if stack.size == 0 {
+                grid.take_slot(slot_index);
+            }
+        }
+    }
+
+    if let Some(output) = container_manager.get_container_mut(&ContainerType::CraftingOutput(station)) {
+        output.take_slot(0);
+    }
+}
+
+/// How many items of `stack` a container could still take, across the given slots
+fn available_capacity(container: &SlotContainer, stack: &ItemStack, slots: Range<usize>) -> u32 {
+    let max_size = stack.item.map(|item| item.properties.max_stack_size).unwrap_or(0);
+
+    (slots.start..slots.end.min(container.len()))
+        .filter(|slot_index| container.can_insert(*slot_index, stack))
+        .map(|slot_index| match container.get_slot(slot_index) {
+            None => max_size,
+            Some(existing) if stack.can_merge_with(existing) => max_size.saturating_sub(existing.size),
+            Some(_) => 0,
+        })
+        .sum()
+}
@@ -1,9 +1,15 @@
 use crate::world::inventory::components::HeldItem;
 use crate::world::inventory::containers::{ContainerManager, ContainerType};
+use crate::world::inventory::equipment::EQUIPMENT_OFFHAND_SLOT;
+use crate::world::inventory::routing::ShiftClickRouting;
 use crate::world::inventory::inventory::SlotContainer;
 use crate::world::inventory::item_stack::ItemStack;
 
 pub fn process_left_click(slot_index: usize, inventory: &mut SlotContainer, held_item: &mut HeldItem) {
+    if reject_held_stack(slot_index, inventory, held_item) {
+        return;
+    }
+
     let slot_stack = inventory.take_slot(slot_index);
 
     match (&mut held_item.stack, slot_stack) {
@@ -30,9 +36,58 @@ pub fn process_left_click(slot_index: usize, inventory: &mut SlotContainer, held
     }
 }
 
+/// Pulls items matching the held stack out of every visible container onto the cursor
+///
+/// Partial stacks are emptied first so full stacks stay intact where possible, output slots are skipped.
+/// Returns how many items were gathered.
+pub fn gather_onto_held(container_manager: &mut ContainerManager, held_item: &mut HeldItem) -> u32 {
+    let Some(held_stack) = held_item.stack.as_mut() else { return 0; };
+    let Some(item) = held_stack.item else { return 0; };
+    let max_stack_size = item.properties.max_stack_size;
+    let starting_size = held_stack.size;
+
+    let visible: Vec<ContainerType> = container_manager.layouts.iter()
+        .flat_map(|layout| layout.container_types().cloned())
+        .collect();
+
+    for partial_only in [true, false] {
+        for container_type in &visible {
+            let Some(container) = container_manager.containers.get_mut(container_type) else { continue; };
+
+            for slot_index in 0..container.len() {
+                if held_stack.size >= max_stack_size {
+                    return held_stack.size - starting_size;
+                }
+                if container.is_output_slot(slot_index) {
+                    continue;
+                }
+
+                let Some(mut slot_stack) = container.get_slot_mut(slot_index) else { continue; };
+                if !slot_stack.can_merge_with(held_stack) || (partial_only && slot_stack.size >= max_stack_size) {
+                    continue;
+                }
+
+                let taken = slot_stack.size.min(max_stack_size - held_stack.size);
+                slot_stack.size -= taken;
+                held_stack.size += taken;
+
+                if slot_stack.size == 0 {
+                    container.set_slot_unchecked(slot_index, None);
+                }
+            }
+        }
+    }
+
+    held_stack.size - starting_size
+}
+
 pub fn process_right_click(slot_index: usize, inventory: &mut SlotContainer, held_item: &mut HeldItem) {
+    if reject_held_stack(slot_index, inventory, held_item) {
+        return;
+    }
+
     match (&mut held_item.stack, inventory.get_slot_mut(slot_index)) {
-        (None, Some(slot_stack)) => {
+        (None, Some(mut slot_stack)) => {
             if let Some(half_stack) = slot_stack.split_half() {
                 held_item.stack = Some(half_stack);
             } else {
@@ -40,8 +95,8 @@ pub fn process_right_click(slot_index: usize, inventory: &mut SlotContainer, hel
                 held_item.stack = removed_stack;
             }
         }
-        (Some(held_stack), Some(slot_stack)) => {
-            if held_stack.can_merge_with(slot_stack) && held_stack.size > 0 {
+        (Some(held_stack), Some(mut slot_stack)) => {
+            if held_stack.can_merge_with(&slot_stack) && held_stack.size > 0 {
                 if slot_stack.size < slot_stack.item.unwrap().properties.max_stack_size {
                     held_stack.size -= 1;
                     slot_stack.size += 1;
@@ -54,10 +109,7 @@ pub fn process_right_click(slot_index: usize, inventory: &mut SlotContainer, hel
         (Some(held_stack), None) => {
             if held_stack.size > 1 {
                 held_stack.size -= 1;
-                let _ = inventory.set_slot(slot_index, Some(ItemStack::new(
-                    held_stack.item.unwrap().clone(),
-                    1
-                )));
+                let _ = inventory.set_slot(slot_index, Some(held_stack.with_size(1)));
             } else {
                 let _ = inventory.set_slot(slot_index, Some(held_stack.clone()));
                 held_item.stack = None;
@@ -67,6 +119,37 @@ pub fn process_right_click(slot_index: usize, inventory: &mut SlotContainer, hel
     }
 }
 
+/// Handles clicks with a held stack the slot's filter doesn't accept, returns `true` if the click was handled
+///
+/// Output-only slots are still taken from, other slots ignore the click.
+fn reject_held_stack(slot_index: usize, inventory: &mut SlotContainer, held_item: &mut HeldItem) -> bool {
+    let Some(held_stack) = &held_item.stack else { return false; };
+
+    if inventory.can_insert(slot_index, held_stack) {
+        return false;
+    }
+
+    if inventory.is_output_slot(slot_index) {
+        take_from_output_slot(slot_index, inventory, held_item);
+    }
+    true
+}
+
+/// Picks up a take-only slot (like a furnace output) onto the cursor, merging into the held stack if possible
+pub fn take_from_output_slot(slot_index: usize, container: &mut SlotContainer, held_item: &mut HeldItem) {
+    let Some(mut slot_stack) = container.take_slot(slot_index) else { return; };
+
+    match &mut held_item.stack {
+        None => held_item.stack = Some(slot_stack),
+        Some(held_stack) => {
+            if !slot_stack.try_merge(held_stack) {
+                // whatever didn't fit stays in the slot
+                let _ = container.set_slot(slot_index, Some(slot_stack));
+            }
+        }
+    }
+}
+
 pub fn deposit_single_item(slot_index: usize, container: &mut SlotContainer, held_item: &mut HeldItem) {
     let Some(held_stack) = &mut held_item.stack else { return; };
 
@@ -75,17 +158,21 @@ pub fn deposit_single_item(slot_index: usize, container: &mut SlotContainer, hel
         return;
     }
 
+    if !container.can_insert(slot_index, held_stack) {
+        return;
+    }
+
     match container.get_slot_mut(slot_index) {
         None => {
             held_stack.size -= 1;
-            let single_item = ItemStack::new(held_stack.item.unwrap(), 1);
+            let single_item = held_stack.with_size(1);
             let _ = container.set_slot(slot_index, Some(single_item));
             if held_stack.size == 0 {
                 held_item.stack = None;
             }
         }
-        Some(slot_stack) => {
-            if held_stack.can_merge_with(slot_stack) {
+        Some(mut slot_stack) => {
+            if held_stack.can_merge_with(&slot_stack) {
                 let max_size = slot_stack.item.unwrap().properties.max_stack_size;
                 if slot_stack.size < max_size {
                     held_stack.size -= 1;
@@ -104,15 +191,19 @@ pub fn place_stack_in_slot(
     slot_index: usize,
     stack: ItemStack,
 ) -> Option<ItemStack> {
+    if !container.can_insert(slot_index, &stack) {
+        return Some(stack);
+    }
+
     match container.get_slot_mut(slot_index) {
         None => {
             // Empty slot - place the entire stack
             let _ = container.set_slot(slot_index, Some(stack));
             None
         }
-        Some(existing_stack) => {
+        Some(mut existing_stack) => {
             // Slot has items - try to merge
-            if stack.can_merge_with(existing_stack) {
+            if stack.can_merge_with(&existing_stack) {
                 let max_size = existing_stack.item.unwrap().properties.max_stack_size;
                 let available_space = max_size.saturating_sub(existing_stack.size);
                 let to_add = available_space.min(stack.size);
@@ -123,7 +214,7 @@ pub fn place_stack_in_slot(
                 if to_add == stack.size {
                     None // All items placed
                 } else {
-                    Some(ItemStack::new(stack.item.unwrap(), stack.size - to_add))
+                    Some(stack.with_size(stack.size - to_add))
                 }
             } else {
                 // Can't merge - return the entire stack
@@ -141,6 +232,10 @@ pub fn filter_valid_distribution_slots(
     slots.iter()
         .filter(|(container_type, slot_index)| {
             if let Some(container) = container_manager.get_container(container_type) {
+                if !container.can_insert(*slot_index, held_stack) {
+                    return false;
+                }
+
                 match container.get_slot(*slot_index) {
                     None => true, // Empty slot is always valid
                     Some(existing_stack) => {
@@ -161,7 +256,13 @@ pub fn process_shift_click(
     slot_index: usize,
     source_container_type: &ContainerType,
     container_manager: &mut ContainerManager,
+    routing: &ShiftClickRouting,
 ) {
+    // Crafting outputs craft instead of moving, see `craft_all_into_targets`
+    if matches!(source_container_type, ContainerType::CraftingOutput(_)) {
+        return;
+    }
+
     // Get the item stack from the source slot
     let source_container = match container_manager.get_container_mut(source_container_type) {
         Some(container) => container,
@@ -173,12 +274,13 @@ pub fn process_shift_click(
     };
 
     // Determine target containers based on source and current UI mode
-    let target_containers = get_shift_click_targets(source_container_type, &container_manager.ui_mode);
+    let targets = routing.targets(source_container_type, container_manager);
     
     // Try to place the item stack in target containers
-    for target_type in target_containers {
-        if let Some(target_container) = container_manager.get_container_mut(&target_type) {
-            if let Some(remaining_stack) = try_place_stack_in_container(target_container, item_stack) {
+    for target in targets {
+        if let Some(target_container) = container_manager.get_container_mut(&target.container_type) {
+            let slots = target.slots.unwrap_or(0..target_container.len());
+            if let Some(remaining_stack) = target_container.try_place_stack_in_slots(item_stack, slots) {
                 item_stack = remaining_stack;
             } else {
                 // All items placed successfully
@@ -193,64 +295,110 @@ pub fn process_shift_click(
     }
 }
 
-fn get_shift_click_targets(source_type: &ContainerType, ui_mode: &crate::world::inventory::containers::UIMode) -> Vec<ContainerType> {
-    use crate::world::inventory::containers::UIMode;
-    
-    match (source_type, ui_mode) {
-        // From hotbar to player inventory
-        (ContainerType::Hotbar, UIMode::InventoryOpen) => {
-            vec![ContainerType::PlayerInventory]
-        }
-        // From hotbar to chest (when chest is open)
-        (ContainerType::Hotbar, UIMode::ChestOpen(chest_id)) => {
-            vec![ContainerType::Chest(*chest_id), ContainerType::PlayerInventory]
-        }
-        // From player inventory to hotbar
-        (ContainerType::PlayerInventory, UIMode::InventoryOpen) => {
-            vec![ContainerType::Hotbar]
-        }
-        // From player inventory to chest (when chest is open)
-        (ContainerType::PlayerInventory, UIMode::ChestOpen(chest_id)) => {
-            vec![ContainerType::Chest(*chest_id)]
-        }
-        // From chest to player inventory then hotbar
-        (ContainerType::Chest(_), UIMode::ChestOpen(_)) => {
-            vec![ContainerType::PlayerInventory, ContainerType::Hotbar]
-        }
-        // Default case - no valid targets
-        _ => vec![]
+/// Swaps a slot's contents with a hotbar slot directly, without going through the held item
+///
+/// Returns `false` if either stack isn't accepted by the slot it would move into.
+/// Crafting outputs can't be swapped since taking from them consumes ingredients.
+pub fn swap_with_hotbar(
+    container_type: &ContainerType,
+    slot_index: usize,
+    hotbar_index: usize,
+    container_manager: &mut ContainerManager,
+) -> bool {
+    if matches!(container_type, ContainerType::CraftingOutput(_)) {
+        return false;
+    }
+    if *container_type == ContainerType::Hotbar && slot_index == hotbar_index {
+        return false;
+    }
+
+    let Some(container) = container_manager.get_container(container_type) else { return false; };
+    let Some(hotbar) = container_manager.get_container(&ContainerType::Hotbar) else { return false; };
+    if slot_index >= container.len() || hotbar_index >= hotbar.len() {
+        return false;
+    }
+
+    let slot_stack = container.get_slot(slot_index).cloned();
+    let hotbar_stack = hotbar.get_slot(hotbar_index).cloned();
+
+    if slot_stack.is_none() && hotbar_stack.is_none() {
+        return false;
+    }
+
+    let slot_accepts = hotbar_stack.iter().all(|stack| container.can_insert(slot_index, stack));
+    let hotbar_accepts = slot_stack.iter().all(|stack| hotbar.can_insert(hotbar_index, stack));
+    if !slot_accepts || !hotbar_accepts {
+        return false;
+    }
+
+    if let Some(container) = container_manager.get_container_mut(container_type) {
+        container.set_slot_unchecked(slot_index, hotbar_stack);
     }
+    if let Some(hotbar) = container_manager.get_container_mut(&ContainerType::Hotbar) {
+        hotbar.set_slot_unchecked(hotbar_index, slot_stack);
+    }
+
+    true
 }
 
-fn try_place_stack_in_container(container: &mut SlotContainer, mut stack: ItemStack) -> Option<ItemStack> {
-    let slot_count = container.len();
-    
-    // First pass: try to merge with existing stacks
-    for slot_index in 0..slot_count {
-        if let Some(existing_stack) = container.get_slot_mut(slot_index) {
-            if stack.can_merge_with(existing_stack) {
-                let max_size = existing_stack.item.unwrap().properties.max_stack_size;
-                let available_space = max_size.saturating_sub(existing_stack.size);
-                let to_add = available_space.min(stack.size);
-                
-                existing_stack.size += to_add;
-                stack.size -= to_add;
-                
-                if stack.size == 0 {
-                    return None; // All items placed
-                }
-            }
-        }
+/// Swaps the selected hotbar slot with the offhand slot, returns `false` if the hotbar item can't be held in the offhand
+pub fn swap_with_offhand(hotbar_index: usize, container_manager: &mut ContainerManager) -> bool {
+    let hotbar_stack = container_manager.get_container(&ContainerType::Hotbar).and_then(|hotbar| hotbar.get_slot(hotbar_index)).cloned();
+    let offhand_stack = container_manager.get_container(&ContainerType::Equipment).and_then(|equipment| equipment.get_slot(EQUIPMENT_OFFHAND_SLOT)).cloned();
+
+    if hotbar_stack.is_none() && offhand_stack.is_none() {
+        return false;
     }
-    
-    // Second pass: place remaining items in empty slots
-    for slot_index in 0..slot_count {
-        if container.get_slot(slot_index).is_none() {
-            let _ = container.set_slot(slot_index, Some(stack));
-            return None; // All items placed
+
+    let offhand_accepts = container_manager.get_container(&ContainerType::Equipment)
+        .is_some_and(|equipment| hotbar_stack.iter().all(|stack| equipment.can_insert(EQUIPMENT_OFFHAND_SLOT, stack)));
+    if !offhand_accepts {
+        return false;
+    }
+
+    if let Some(hotbar) = container_manager.get_container_mut(&ContainerType::Hotbar) {
+        if hotbar.set_slot(hotbar_index, offhand_stack).is_err() {
+            return false;
         }
     }
-    
-    // Return remaining items if container is full
-    Some(stack)
-}
\ No newline at end of file
+
+    if let Some(equipment) = container_manager.get_container_mut(&ContainerType::Equipment) {
+        let _ = equipment.set_slot(EQUIPMENT_OFFHAND_SLOT, hotbar_stack);
+    }
+
+    true
+}
+
+/// Takes one item (or the whole stack) out of a slot so it can be dropped into the world
+pub fn take_for_drop(slot_index: usize, container: &mut SlotContainer, whole_stack: bool) -> Option<ItemStack> {
+    if whole_stack {
+        return container.take_slot(slot_index);
+    }
+
+    let mut stack = container.get_slot_mut(slot_index)?;
+    stack.size -= 1;
+    let dropped = stack.with_size(1);
+
+    if stack.size == 0 {
+        container.set_slot_unchecked(slot_index, None);
+    }
+
+    Some(dropped)
+}
+
+/// Takes one item (or the whole stack) off the cursor so it can be dropped into the world
+pub fn take_held_for_drop(held_item: &mut HeldItem, whole_stack: bool) -> Option<ItemStack> {
+    if whole_stack {
+        return held_item.stack.take();
+    }
+
+    let stack = held_item.stack.as_mut()?;
+    stack.size -= 1;
+    let dropped = stack.with_size(1);
+
+    if stack.size == 0 {
+        held_item.stack = None;
+    }
+
+    Some(dropped)
+}
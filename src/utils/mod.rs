@@ -1,2 +1,3 @@
 pub mod slot_finder;
-pub mod item_operations;
\ No newline at end of file
+pub mod item_operations;
+pub mod crafting;
\ No newline at end of file
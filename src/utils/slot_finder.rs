@@ -9,6 +9,9 @@ pub fn find_slot_under_cursor<'a>(
     for (slot, transform, node) in slot_query {
         let slot_pos = transform.translation().truncate();
         let slot_size = node.size();
+        if slot_size == Vec2::ZERO {
+            continue; // hidden, e.g. in a closed container's panel
+        }
         let slot_rect = bevy::math::Rect::from_center_size(slot_pos, slot_size);
 
         if slot_rect.contains(cursor_pos) {
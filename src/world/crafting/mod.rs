@@ -0,0 +1,68 @@
+pub mod recipe;
+pub mod recipes;
+
+/// `define_recipes` works like `define_items`: each entry becomes a `pub const` `Recipe`,
+/// and a `RECIPES` slice collects all of them.
+///
+/// ```ignore
+/// define_recipes! {
+///     // rows of ingredients, `_` is an empty cell
+///     SHIELD => "wooden_shield" shaped [[STICK, STICK], [STICK, STICK]] => WOODEN_SHIELD x 1,
+///     // like shaped, but the left-right mirror image matches too
+///     STAFF => "ring_staff" mirrored [[_, RING], [STICK, _]] => RING_STAFF x 1,
+///     // any arrangement of the listed ingredients
+///     SCROLL => "scroll" shapeless [PAPER, STICK] => SCROLL x 1,
+/// }
+/// ```
+#[macro_export]
+macro_rules! define_recipes {
+
+    // internal rule: a single grid cell, `_` means the cell must be empty
+    (@cell _) => { None };
+    (@cell $ingredient:ident) => { Some($ingredient.identifier) };
+
+    // internal rules: the three kinds of recipe shapes
+    (@shape shaped [$([$($cell:tt),* $(,)?]),* $(,)?]) => {
+        RecipeShape::Shaped {
+            pattern: &[$(&[$($crate::define_recipes!(@cell $cell)),*]),*],
+            mirrored: false,
+        }
+    };
+    (@shape mirrored [$([$($cell:tt),* $(,)?]),* $(,)?]) => {
+        RecipeShape::Shaped {
+            pattern: &[$(&[$($crate::define_recipes!(@cell $cell)),*]),*],
+            mirrored: true,
+        }
+    };
+    (@shape shapeless [$($ingredient:ident),* $(,)?]) => {
+        RecipeShape::Shapeless {
+            ingredients: &[$($ingredient.identifier),*],
+        }
+    };
+
+    // the public pattern, same spirit as `define_items`:
+    // NAME => "identifier" <shaped|mirrored|shapeless> [ingredients] => RESULT x count
+    (
+        $(
+            $recipe:ident => $identifier:literal $kind:ident $ingredients:tt => $result:ident x $count:literal
+        ),*
+        $(,)?
+    )
+    =>
+    {
+        $(
+            pub const $recipe: Recipe = Recipe {
+                identifier: $identifier,
+                shape: $crate::define_recipes!(@shape $kind $ingredients),
+                result: $result.identifier,
+                count: $count,
+            };
+        )*
+
+        pub const RECIPES: &[&Recipe] = &[
+            $(
+                &$recipe
+            ),*
+        ];
+    };
+}
@@ -0,0 +1,184 @@
+use crate::world::crafting::recipes::RECIPES;
+use crate::world::inventory::inventory::SlotContainer;
+use bevy::prelude::Resource;
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RecipeShape {
+    /// Rows of ingredient identifiers (`None` is an empty cell), matched anywhere in the grid
+    Shaped {
+        pattern: &'static [&'static [Option<&'static str>]],
+        /// Also match the left-right mirror image of the pattern
+        mirrored: bool,
+    },
+
+    /// Ingredient identifiers in any arrangement, one per slot
+    Shapeless {
+        ingredients: &'static [&'static str],
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Recipe {
+    /// The Recipe's internal ID
+    pub identifier: &'static str,
+
+    /// Which ingredients go where
+    pub shape: RecipeShape,
+
+    /// Identifier of the crafted item
+    pub result: &'static str,
+
+    /// How many items one craft produces
+    pub count: u32,
+}
+
+impl Recipe {
+
+    /// Checks a square crafting grid (`width` x `width`, row-major) against this recipe
+    pub fn matches(&self, grid: &[Option<&str>], width: usize) -> bool {
+        match &self.shape {
+            RecipeShape::Shaped { pattern, mirrored } => {
+                matches_shaped(pattern, grid, width, false)
+                    || (*mirrored && matches_shaped(pattern, grid, width, true))
+            }
+            RecipeShape::Shapeless { ingredients } => {
+                let mut remaining: Vec<&str> = ingredients.to_vec();
+
+                for identifier in grid.iter().flatten() {
+                    match remaining.iter().position(|ingredient| ingredient == identifier) {
+                        Some(index) => { remaining.swap_remove(index); }
+                        None => return false, // something that isn't part of the recipe
+                    }
+                }
+
+                remaining.is_empty()
+            }
+        }
+    }
+}
+
+/// Compares the occupied area of the grid with the occupied area of the pattern, cell by cell
+///
+/// Empty outer rows and columns of the pattern don't count, so they don't have to fit in the grid.
+fn matches_shaped(pattern: &[&[Option<&str>]], grid: &[Option<&str>], width: usize, mirrored: bool) -> bool {
+    let pattern_cell = |row: usize, column: usize| pattern.get(row).and_then(|cells| cells.get(column)).copied().flatten();
+
+    let pattern_occupied = pattern.iter().enumerate()
+        .flat_map(|(row, cells)| cells.iter().enumerate().filter(|(_, cell)| cell.is_some()).map(move |(column, _)| (row, column)));
+    let grid_occupied = (0..grid.len())
+        .filter(|&index| grid[index].is_some())
+        .map(|index| (index / width, index % width));
+
+    let Some((pattern_top, pattern_left, height, pattern_width)) = bounding_box(pattern_occupied) else {
+        return false; // empty pattern
+    };
+    let Some((top, left, grid_height, grid_width)) = bounding_box(grid_occupied) else {
+        return false; // empty grid
+    };
+
+    if grid_height != height || grid_width != pattern_width {
+        return false;
+    }
+
+    for row in 0..height {
+        for column in 0..pattern_width {
+            let pattern_column = if mirrored { pattern_width - 1 - column } else { column };
+            let expected = pattern_cell(pattern_top + row, pattern_left + pattern_column);
+            let actual = grid[(top + row) * width + left + column];
+
+            if expected != actual {
+                return false;
+            }
+        }
+    }
+
+    true
+}
+
+/// Top row, left column, height and width of the smallest rectangle holding every `(row, column)`
+fn bounding_box(cells: impl Iterator<Item = (usize, usize)>) -> Option<(usize, usize, usize, usize)> {
+    let (top, left, bottom, right) = cells.fold(None, |bounds: Option<(usize, usize, usize, usize)>, (row, column)| {
+        Some(match bounds {
+            Some((top, left, bottom, right)) => (top.min(row), left.min(column), bottom.max(row), right.max(column)),
+            None => (row, column, row, column),
+        })
+    })?;
+
+    Some((top, left, bottom - top + 1, right - left + 1))
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RecipeError {
+    DuplicateIdentifier { identifier: String },
+    NoIngredients { identifier: String }, // an empty pattern or ingredient list, it could never match
+}
+
+impl fmt::Display for RecipeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RecipeError::DuplicateIdentifier { identifier } => {
+                write!(f, "a recipe with identifier \"{}\" is already registered", identifier)
+            }
+            RecipeError::NoIngredients { identifier } => write!(f, "recipe \"{}\" has no ingredients", identifier),
+        }
+    }
+}
+
+impl std::error::Error for RecipeError {}
+
+/// Every recipe known to the game, starting with the ones from `define_recipes!`
+#[derive(Resource)]
+pub struct RecipeRegistry {
+    recipes: Vec<Recipe>,
+}
+
+impl Default for RecipeRegistry {
+    fn default() -> Self {
+        let mut registry = Self { recipes: Vec::new() };
+
+        for recipe in RECIPES {
+            registry.register((*recipe).clone()).expect("duplicate identifier or empty recipe in define_recipes!");
+        }
+
+        registry
+    }
+}
+
+impl RecipeRegistry {
+
+    /// Adds a recipe, refusing identifiers that are already taken and recipes without ingredients
+    pub fn register(&mut self, recipe: Recipe) -> Result<(), RecipeError> {
+        if self.get(recipe.identifier).is_some() {
+            return Err(RecipeError::DuplicateIdentifier { identifier: recipe.identifier.to_string() });
+        }
+
+        let has_ingredients = match &recipe.shape {
+            RecipeShape::Shaped { pattern, .. } => pattern.iter().flat_map(|row| row.iter()).any(Option::is_some),
+            RecipeShape::Shapeless { ingredients } => !ingredients.is_empty(),
+        };
+        if !has_ingredients {
+            return Err(RecipeError::NoIngredients { identifier: recipe.identifier.to_string() });
+        }
+
+        self.recipes.push(recipe);
+        Ok(())
+    }
+
+    pub fn get(&self, identifier: &str) -> Option<&Recipe> {
+        self.recipes.iter().find(|recipe| recipe.identifier == identifier)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Recipe> {
+        self.recipes.iter()
+    }
+
+    /// Finds the first recipe matching the contents of a square crafting grid
+    pub fn find_match(&self, grid: &SlotContainer, width: usize) -> Option<&Recipe> {
+        let cells: Vec<Option<&str>> = (0..grid.len())
+            .map(|index| grid.get_slot(index).and_then(|stack| stack.item).map(|item| item.identifier))
+            .collect();
+
+        self.recipes.iter().find(|recipe| recipe.matches(&cells, width))
+    }
+}
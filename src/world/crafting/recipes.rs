@@ -0,0 +1,16 @@
+use crate::world::crafting::recipe::*;
+use crate::world::item::items::*;
+use crate::define_recipes;
+
+define_recipes! {
+    WOODEN_SHIELD_RECIPE => "wooden_shield" shaped [
+        [STICK, STICK, STICK],
+        [STICK, STICK, STICK],
+        [_, STICK, _],
+    ] => WOODEN_SHIELD x 1,
+    RING_STAFF_RECIPE => "ring_staff" mirrored [
+        [_, RING],
+        [STICK, _],
+    ] => RING_STAFF x 1,
+    SCROLL_RECIPE => "scroll" shapeless [PAPER, STICK] => SCROLL x 1,
+}
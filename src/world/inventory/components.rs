@@ -1,4 +1,5 @@
 use crate::world::inventory::containers::ContainerType;
+use crate::world::inventory::history::InventoryTransaction;
 use crate::world::inventory::item_stack::ItemStack;
 use bevy::prelude::*;
 
@@ -11,6 +12,13 @@ pub struct InventorySlot {
     pub container_type: ContainerType,
 }
 
+/// What `update_slot_visuals` last drew into a slot, so unchanged slots are skipped
+#[derive(Component, Default)]
+pub struct SlotRenderState {
+    pub revision: Option<u64>,        // `SlotContainer::slot_revision` of the drawn contents
+    pub preview: Option<(u32, bool)>, // drag preview count and whether it was for a right-drag
+}
+
 #[derive(Component)]
 pub struct HeldItemDisplay;
 
@@ -31,12 +39,41 @@ pub struct DragState {
     pub was_left_dragging_this_frame: bool,
     pub left_drag_slots: Vec<(ContainerType, usize)>,
 
+    // Shift + left-click drag (shift-transfers every slot passed over)
+    pub is_shift_dragging: bool,
+    pub was_shift_dragging_this_frame: bool,
+    pub shift_drag_slots: Vec<(ContainerType, usize)>,
+    pub shift_drag_transaction: Option<InventoryTransaction>, // the slots transferred so far, undone at once
+
     pub pickup_slot: Option<(ContainerType, usize)>,
     
     // Current hovered slot for highlighting
     pub current_hovered_slot: Option<(ContainerType, usize)>,
 }
 
+/// Seconds between two clicks on the same slot for them to count as a double-click
+pub const DOUBLE_CLICK_SECS: f32 = 0.3;
+
+/// The last left-click on a slot, used to detect double-clicks
+#[derive(Resource, Default)]
+pub struct LastSlotClick {
+    pub slot: Option<(ContainerType, usize)>,
+    pub time: f32, // `Time::elapsed_seconds` of the click
+}
+
+impl LastSlotClick {
+    /// Records a click, returning `true` if it completes a double-click on the same slot
+    pub fn register(&mut self, slot: (ContainerType, usize), time: f32) -> bool {
+        let is_double_click = self.slot.as_ref() == Some(&slot) && time - self.time <= DOUBLE_CLICK_SECS;
+
+        // a third click starts over instead of counting as another double-click
+        self.slot = if is_double_click { None } else { Some(slot) };
+        self.time = time;
+
+        is_double_click
+    }
+}
+
 #[derive(Resource)]
 pub struct SelectedHotbarSlot {
     pub slot_index: usize,
@@ -51,15 +88,89 @@ impl Default for SelectedHotbarSlot {
 #[derive(Component)]
 pub struct SelectedItemDisplay;
 
+/// Fills up while the selected consumable is being used
+#[derive(Component)]
+pub struct ItemUseProgressBar;
+
 #[derive(Component)]
 pub struct ItemSprite;
 
+/// Details of the hovered stack, following the cursor
+#[derive(Component)]
+pub struct ItemTooltip;
+
+#[derive(Component)]
+pub struct ItemTooltipText;
+
+/// Track of the tooltip's durability bar, hidden for items without durability
+#[derive(Component)]
+pub struct TooltipDurabilityBar;
+
+#[derive(Component)]
+pub struct TooltipDurabilityFill;
+
+/// Extra information for debugging, toggled with F3
+#[derive(Resource, Default)]
+pub struct DebugMode {
+    pub enabled: bool, // tooltips show item identifiers
+}
+
 #[derive(Component)]
 pub struct ItemCountText;
 
 #[derive(Component)]
 pub struct SlotPreviewText;
 
+/// Track of a slot's durability bar, hidden unless the slot holds an item with durability
+#[derive(Component, Default)]
+pub struct SlotDurabilityBar {
+    pub shown: Option<(u128, u128)>, // durability and maximum last drawn, to only redraw on change
+}
+
+#[derive(Component)]
+pub struct SlotDurabilityFill;
+
+/// Cracks drawn over the sprite of items that are about to break
+#[derive(Component)]
+pub struct SlotDamageOverlay;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum FurnaceProgressKind {
+    Burn, // remaining fuel
+    Cook, // smelting progress
+}
+
+#[derive(Component)]
+pub struct FurnaceProgressBar {
+    pub furnace_id: u32,
+    pub kind: FurnaceProgressKind,
+}
+
+/// Slot grid of a container that scrolls row by row once it is taller than the window allows
+#[derive(Component)]
+pub struct ScrollView {
+    pub rows: usize,
+    pub first_row: usize,    // topmost row shown
+    pub visible_rows: usize, // updated from the window height by `scroll_container_views`
+}
+
+impl ScrollView {
+    pub fn new(rows: usize) -> Self {
+        Self { rows, first_row: 0, visible_rows: rows }
+    }
+
+    pub fn is_scrollable(&self) -> bool {
+        self.visible_rows < self.rows
+    }
+}
+
+/// A row of slots inside a `ScrollView`
+#[derive(Component)]
+pub struct ScrollRow {
+    pub view: Entity,
+    pub row: usize,
+}
+
 impl DragState {
     pub fn add_left_drag_slot(&mut self, container_type: ContainerType, index: usize) {
         let slot = (container_type, index);
@@ -68,6 +179,17 @@ impl DragState {
         }
     }
 
+    /// Adds a slot to the shift-drag, returns `false` if it was already passed over
+    pub fn add_shift_drag_slot(&mut self, container_type: ContainerType, index: usize) -> bool {
+        let slot = (container_type, index);
+        if self.shift_drag_slots.contains(&slot) {
+            return false;
+        }
+
+        self.shift_drag_slots.push(slot);
+        true
+    }
+
     pub fn add_right_drag_slot(&mut self, container_type: ContainerType, index: usize) {
         let slot = (container_type, index);
         if !self.right_drag_slots.contains(&slot) {
@@ -0,0 +1,133 @@
+use crate::world::inventory::containers::{ContainerManager, ContainerType};
+use crate::world::inventory::item_stack::ItemStack;
+use crate::world::item::item::{ConsumeEffect, Item};
+use crate::world::item::registry::ItemRegistry;
+use bevy::prelude::Resource;
+
+/// A consumable the player is currently using from the hotbar
+#[derive(Debug, Clone)]
+pub struct ItemUse {
+    pub slot_index: usize,
+    pub item: Item,
+    pub elapsed: f32,
+}
+
+impl ItemUse {
+    pub fn new(slot_index: usize, item: Item) -> Self {
+        Self { slot_index, item, elapsed: 0.0 }
+    }
+
+    /// Seconds the item has to be used for before it is consumed
+    pub fn duration(&self) -> f32 {
+        self.item.properties.use_duration_ms as f32 / 1000.0
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.elapsed >= self.duration()
+    }
+
+    /// Progress of the current use, from 0.0 to 1.0
+    pub fn fraction(&self) -> f32 {
+        let duration = self.duration();
+        if duration <= 0.0 {
+            1.0
+        } else {
+            (self.elapsed / duration).clamp(0.0, 1.0)
+        }
+    }
+}
+
+#[derive(Resource, Default)]
+pub struct ItemUseState {
+    pub current: Option<ItemUse>,
+}
+
+impl ItemUseState {
+    pub fn fraction(&self) -> f32 {
+        self.current.as_ref().map_or(0.0, ItemUse::fraction)
+    }
+
+    pub fn cancel(&mut self) {
+        self.current = None;
+    }
+}
+
+/// What consuming a single item did to the inventory
+#[derive(Debug, Clone)]
+pub struct ConsumedItem {
+    pub item: Item,
+    pub remainder: Option<Item>,
+    pub leftover: Option<ItemStack>, // remainder that didn't fit anywhere
+}
+
+/// Consumes one item from a container slot, leaving its remainder item behind
+///
+/// The remainder takes the consumed item's place once the stack runs out, otherwise it goes into
+/// the hotbar and then the player inventory, what doesn't fit is returned as `leftover`.
+/// Returns `None` if the slot doesn't hold a consumable.
+pub fn consume_from_slot(
+    container_manager: &mut ContainerManager,
+    container_type: &ContainerType,
+    slot_index: usize,
+    item_registry: &ItemRegistry,
+) -> Option<ConsumedItem> {
+    let container = container_manager.get_container_mut(container_type)?;
+    let mut stack = container.get_slot_mut(slot_index)?;
+    let item = stack.item.filter(|item| item.properties.is_consumable)?;
+
+    stack.size -= 1;
+    if stack.size == 0 {
+        container.set_slot_unchecked(slot_index, None);
+    }
+
+    let remainder = item.properties.remainder.and_then(|identifier| item_registry.get(identifier));
+    let mut leftover = remainder.map(|remainder| ItemStack::new(remainder, 1));
+
+    if let Some(stack) = &leftover {
+        if container.get_slot(slot_index).is_none() && container.can_insert(slot_index, stack) {
+            container.set_slot_unchecked(slot_index, leftover.take());
+        }
+    }
+
+    for target in [ContainerType::Hotbar, ContainerType::PlayerInventory] {
+        let Some(stack) = leftover.take() else { break; };
+        leftover = match container_manager.get_container_mut(&target) {
+            Some(container) => container.try_place_stack(stack),
+            None => Some(stack),
+        };
+    }
+
+    Some(ConsumedItem { item, remainder, leftover })
+}
+
+/// Demo player stats restored by consumables, see `apply_consume_effects`
+#[derive(Resource, Debug, Clone)]
+pub struct PlayerVitals {
+    pub health: u32,
+    pub max_health: u32,
+    pub hunger: u32,
+    pub max_hunger: u32,
+}
+
+impl Default for PlayerVitals {
+    fn default() -> Self {
+        Self { health: 10, max_health: 20, hunger: 10, max_hunger: 20 }
+    }
+}
+
+impl PlayerVitals {
+    /// Applies an effect, returning `false` for effects these vitals don't know about
+    pub fn apply(&mut self, effect: &ConsumeEffect) -> bool {
+        match effect {
+            ConsumeEffect::RestoreHealth(amount) => {
+                self.health = (self.health + amount).min(self.max_health);
+                true
+            }
+            ConsumeEffect::RestoreHunger(amount) => {
+                self.hunger = (self.hunger + amount).min(self.max_hunger);
+                true
+            }
+            ConsumeEffect::Custom(_) => false,
+        }
+    }
+}
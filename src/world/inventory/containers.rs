@@ -1,13 +1,87 @@
+use crate::world::inventory::equipment::*;
+use crate::world::inventory::filter::SlotFilter;
+use crate::world::inventory::furnace::{FURNACE_FUEL_SLOT, FURNACE_INPUT_SLOT, FURNACE_OUTPUT_SLOT, FURNACE_SLOT_COUNT};
 use crate::world::inventory::inventory::SlotContainer;
+use crate::world::inventory::kind::{ContainerKind, ContainerKindError, ContainerKindRegistry};
+use crate::world::inventory::item_stack::ItemStack;
+use crate::world::inventory::sort::{sort_container, SortSettings};
+use crate::world::item::item::{ConsumeEffect, EquipmentSlot, Item};
 use bevy::prelude::*;
 
 // Define container types
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub enum ContainerType {
     PlayerInventory,
     Hotbar,
     Chest(u32), // Now takes a chest ID
-    // Add more as needed: Furnace, CraftingTable, etc.
+    CraftingGrid(CraftingStation),
+    CraftingOutput(CraftingStation),
+    Furnace(u32), // Input, fuel and output slot, takes a furnace ID
+    Equipment, // Armour and offhand slots of the player
+    Custom(ContainerKind, u32), // A registered container kind, takes an ID per container of that kind
+}
+
+impl ContainerType {
+    /// Creates an empty container of this type, with the slot filters this type always has
+    pub fn create_container(&self, slot_count: usize) -> SlotContainer {
+        let container = SlotContainer::new(slot_count);
+
+        match self {
+            ContainerType::CraftingOutput(_) => container.with_slot_filter(0, SlotFilter::OutputOnly),
+            ContainerType::Furnace(_) => container
+                .with_slot_filter(FURNACE_INPUT_SLOT, SlotFilter::Smeltable)
+                .with_slot_filter(FURNACE_FUEL_SLOT, SlotFilter::Fuel)
+                .with_slot_filter(FURNACE_OUTPUT_SLOT, SlotFilter::OutputOnly),
+            ContainerType::Equipment => container
+                .with_slot_filter(EQUIPMENT_HEAD_SLOT, SlotFilter::Equipment(EquipmentSlot::Head))
+                .with_slot_filter(EQUIPMENT_CHEST_SLOT, SlotFilter::Equipment(EquipmentSlot::Chest))
+                .with_slot_filter(EQUIPMENT_LEGS_SLOT, SlotFilter::Equipment(EquipmentSlot::Legs))
+                .with_slot_filter(EQUIPMENT_FEET_SLOT, SlotFilter::Equipment(EquipmentSlot::Feet))
+                .with_slot_filter(EQUIPMENT_OFFHAND_SLOT, SlotFilter::OffhandEquipable),
+            // portable kinds hold no other portable containers until the registry says which, see `ContainerKindRegistry::nestable_storage`
+            ContainerType::Custom(kind, _) => kind.slot_filters.iter()
+                .fold(container.with_nestable_storage(kind.is_portable().then(Vec::new)), |container, &(slot_index, filter)| container.with_slot_filter(slot_index, filter)),
+            _ => container,
+        }
+    }
+
+    /// How many slots every container of this type has, `None` for chests, which come in any height
+    pub fn fixed_slot_count(&self) -> Option<usize> {
+        match self {
+            ContainerType::PlayerInventory => Some(27),
+            ContainerType::Hotbar => Some(9),
+            ContainerType::Chest(_) => None,
+            ContainerType::CraftingGrid(station) => Some(station.grid_width() * station.grid_width()),
+            ContainerType::CraftingOutput(_) => Some(1),
+            ContainerType::Furnace(_) => Some(FURNACE_SLOT_COUNT),
+            ContainerType::Equipment => Some(EQUIPMENT_SLOT_COUNT),
+            ContainerType::Custom(kind, _) => Some(kind.slot_count),
+        }
+    }
+
+    /// Whether the contents of this container can be sorted, crafting grids, furnaces and equipment keep their layout
+    pub fn is_sortable(&self) -> bool {
+        match self {
+            ContainerType::Custom(kind, _) => kind.sortable,
+            _ => matches!(self, ContainerType::PlayerInventory | ContainerType::Hotbar | ContainerType::Chest(_)),
+        }
+    }
+}
+
+// Where a crafting grid lives
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum CraftingStation {
+    Player,          // 2x2 grid in the player inventory
+    Table(u32),      // 3x3 crafting table, takes a table ID
+}
+
+impl CraftingStation {
+    pub fn grid_width(&self) -> usize {
+        match self {
+            CraftingStation::Player => 2,
+            CraftingStation::Table(_) => 3,
+        }
+    }
 }
 
 // UI Display modes
@@ -16,10 +90,22 @@ pub enum UIMode {
     HotbarOnly,           // Only hotbar visible (default game state)
     InventoryOpen,        // Hotbar + Player inventory visible
     ChestOpen(u32),      // Hotbar + Player inventory + Specific chest visible
+    CraftingTableOpen(u32), // Hotbar + Player inventory + Specific crafting table visible
+    FurnaceOpen(u32),    // Hotbar + Player inventory + Specific furnace visible
+    CustomOpen(ContainerKind, u32), // Hotbar + Player inventory + Specific container of a registered kind visible
 }
 
+/// Slots per row of every chest
+pub const CHEST_COLUMNS: usize = 9;
+
+/// Rows of a chest opened without being created first, double chests are two of these
+pub const CHEST_ROWS: usize = 3;
+
+/// The most rows `create_chest` (and a save) accepts for a single chest
+pub const MAX_CHEST_ROWS: usize = 64;
+
 // Container layout configuration
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct ContainerLayout {
     pub container_type: ContainerType,
     pub slot_count: usize,
@@ -27,9 +113,17 @@ pub struct ContainerLayout {
     pub columns: usize,
     pub title: String,
     pub position: ContainerPosition,
+    pub linked: Option<(ContainerType, usize)>, // container (and its slot count) shown after the last slot, like the other half of a double chest
 }
 
-#[derive(Debug, Clone)]
+/// Per-chest settings that aren't part of its contents
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ChestInfo {
+    pub title: Option<String>,     // "Chest <id>" (or "Large Chest <id>") when `None`
+    pub linked_chest: Option<u32>, // the other half of a double chest
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub enum ContainerPosition {
     Bottom,     // For hotbar
     Center,     // For player inventory
@@ -37,6 +131,22 @@ pub enum ContainerPosition {
 }
 
 impl ContainerLayout {
+    /// The container and slot index shown in the layout's `index`th slot
+    pub fn slot_at(&self, index: usize) -> Option<(ContainerType, usize)> {
+        if index < self.slot_count {
+            return Some((self.container_type.clone(), index));
+        }
+
+        self.linked.as_ref()
+            .filter(|(_, linked_count)| index < self.slot_count + linked_count)
+            .map(|(linked_type, _)| (linked_type.clone(), index - self.slot_count))
+    }
+
+    /// Every container this layout shows slots of
+    pub fn container_types(&self) -> impl Iterator<Item = &ContainerType> {
+        std::iter::once(&self.container_type).chain(self.linked.as_ref().map(|(linked_type, _)| linked_type))
+    }
+
     pub fn player_inventory() -> Self {
         Self {
             container_type: ContainerType::PlayerInventory,
@@ -45,6 +155,7 @@ impl ContainerLayout {
             columns: 9,
             title: "Inventory".to_string(),
             position: ContainerPosition::Center,
+            linked: None,
         }
     }
 
@@ -56,6 +167,7 @@ impl ContainerLayout {
             columns: 9,
             title: "".to_string(), // No title for hotbar
             position: ContainerPosition::Bottom,
+            linked: None,
         }
     }
 
@@ -67,6 +179,59 @@ impl ContainerLayout {
             columns: 9,
             title: format!("Chest {}", chest_id),
             position: ContainerPosition::Top,
+            linked: None,
+        }
+    }
+
+    pub fn crafting_grid(station: CraftingStation) -> Self {
+        let width = station.grid_width();
+        Self {
+            container_type: ContainerType::CraftingGrid(station),
+            slot_count: width * width,
+            rows: width,
+            columns: width,
+            title: match station {
+                CraftingStation::Player => "Crafting".to_string(),
+                CraftingStation::Table(table_id) => format!("Crafting Table {}", table_id),
+            },
+            position: ContainerPosition::Top,
+            linked: None,
+        }
+    }
+
+    pub fn crafting_output(station: CraftingStation) -> Self {
+        Self {
+            container_type: ContainerType::CraftingOutput(station),
+            slot_count: 1,
+            rows: 1,
+            columns: 1,
+            title: "Result".to_string(),
+            position: ContainerPosition::Top,
+            linked: None,
+        }
+    }
+
+    pub fn equipment() -> Self {
+        Self {
+            container_type: ContainerType::Equipment,
+            slot_count: EQUIPMENT_SLOT_COUNT,
+            rows: 3, // same height as the player inventory: head, chest / legs, feet / offhand
+            columns: 2,
+            title: "Equipment".to_string(),
+            position: ContainerPosition::Center,
+            linked: None,
+        }
+    }
+
+    pub fn furnace(furnace_id: u32) -> Self {
+        Self {
+            container_type: ContainerType::Furnace(furnace_id),
+            slot_count: FURNACE_SLOT_COUNT,
+            rows: 1,
+            columns: FURNACE_SLOT_COUNT,
+            title: format!("Furnace {}", furnace_id),
+            position: ContainerPosition::Top,
+            linked: None,
         }
     }
 }
@@ -79,6 +244,8 @@ pub struct ContainerManager {
     pub layouts: Vec<ContainerLayout>, // All active layouts to display
     pub available_chests: Vec<u32>, // List of available chest IDs
     pub active_chest_id: Option<u32>, // Currently opened chest
+    pub chest_info: std::collections::HashMap<u32, ChestInfo>, // Titles and double chest links, by chest ID
+    pub portable_origin: Option<(ContainerType, usize)>, // slot of the stack whose portable container is shown
 }
 
 impl Default for ContainerManager {
@@ -88,6 +255,9 @@ impl Default for ContainerManager {
         // Create the default containers
         containers.insert(ContainerType::PlayerInventory, SlotContainer::new(27));
         containers.insert(ContainerType::Hotbar, SlotContainer::new(9));
+        containers.insert(ContainerType::Equipment, ContainerType::Equipment.create_container(EQUIPMENT_SLOT_COUNT));
+        containers.insert(ContainerType::CraftingGrid(CraftingStation::Player), SlotContainer::new(4));
+        containers.insert(ContainerType::CraftingOutput(CraftingStation::Player), ContainerType::CraftingOutput(CraftingStation::Player).create_container(1));
 
         // Create initial chests
         let available_chests = vec![1, 2, 3];
@@ -95,44 +265,203 @@ impl Default for ContainerManager {
             containers.insert(ContainerType::Chest(chest_id), SlotContainer::new(27));
         }
 
+        containers.insert(ContainerType::Furnace(1), ContainerType::Furnace(1).create_container(FURNACE_SLOT_COUNT));
+
         Self {
             containers,
             ui_mode: UIMode::HotbarOnly,
             layouts: vec![ContainerLayout::hotbar()],
             available_chests,
             active_chest_id: None,
+            chest_info: std::collections::HashMap::new(),
+            portable_origin: None,
         }
     }
 }
 
 impl ContainerManager {
     pub fn open_inventory(&mut self) {
+        self.return_crafting_grids();
         self.ui_mode = UIMode::InventoryOpen;
         self.layouts = vec![
+            ContainerLayout::crafting_grid(CraftingStation::Player),
+            ContainerLayout::crafting_output(CraftingStation::Player),
+            ContainerLayout::equipment(),
             ContainerLayout::player_inventory(),
             ContainerLayout::hotbar(),
         ];
     }
 
     pub fn close_inventory(&mut self) {
+        self.return_crafting_grids();
         self.ui_mode = UIMode::HotbarOnly;
         self.layouts = vec![ContainerLayout::hotbar()];
     }
 
-    pub fn open_chest(&mut self, chest_id: u32) {
-        // Create chest if it doesn't exist
-        let chest_type = ContainerType::Chest(chest_id);
-        if !self.containers.contains_key(&chest_type) {
-            self.containers.insert(chest_type.clone(), SlotContainer::new(27));
-            if !self.available_chests.contains(&chest_id) {
-                self.available_chests.push(chest_id);
+    pub fn open_crafting_table(&mut self, table_id: u32) {
+        self.return_crafting_grids();
+
+        let station = CraftingStation::Table(table_id);
+        self.containers.entry(ContainerType::CraftingGrid(station)).or_insert_with(|| SlotContainer::new(9));
+        self.containers.entry(ContainerType::CraftingOutput(station)).or_insert_with(|| ContainerType::CraftingOutput(station).create_container(1));
+
+        self.active_chest_id = None;
+        self.ui_mode = UIMode::CraftingTableOpen(table_id);
+        self.layouts = vec![
+            ContainerLayout::crafting_grid(station),
+            ContainerLayout::crafting_output(station),
+            ContainerLayout::equipment(),
+            ContainerLayout::player_inventory(),
+            ContainerLayout::hotbar(),
+        ];
+    }
+
+    pub fn close_crafting_table(&mut self) {
+        self.close_inventory();
+    }
+
+    /// Moves everything left in crafting grids back into the player's inventory (or hotbar)
+    ///
+    /// Called whenever the visible containers change, like closing a crafting table in Minecraft.
+    /// Items that don't fit anywhere stay in the grid.
+    pub fn return_crafting_grids(&mut self) {
+        let grids: Vec<ContainerType> = self.containers.keys()
+            .filter(|container_type| matches!(container_type, ContainerType::CraftingGrid(_)))
+            .cloned()
+            .collect();
+
+        for grid_type in grids {
+            let slot_count = self.get_container(&grid_type).map(SlotContainer::len).unwrap_or(0);
+
+            for slot_index in 0..slot_count {
+                let mut remaining = self.get_container_mut(&grid_type).and_then(|grid| grid.take_slot(slot_index));
+
+                for target_type in [ContainerType::PlayerInventory, ContainerType::Hotbar] {
+                    let Some(stack) = remaining.take() else { break; };
+                    remaining = match self.get_container_mut(&target_type) {
+                        Some(target) => target.try_place_stack(stack),
+                        None => Some(stack),
+                    };
+                }
+
+                if let Some(stack) = remaining {
+                    if let Some(grid) = self.get_container_mut(&grid_type) {
+                        let _ = grid.set_slot(slot_index, Some(stack));
+                    }
+                }
+            }
+
+            if let ContainerType::CraftingGrid(station) = grid_type {
+                if let Some(output) = self.get_container_mut(&ContainerType::CraftingOutput(station)) {
+                    output.take_slot(0);
+                }
             }
         }
+    }
+
+    /// Creates an empty chest with `rows` rows of `CHEST_COLUMNS` slots
+    ///
+    /// Returns `false` if the ID is taken or `rows` isn't between 1 and `MAX_CHEST_ROWS`.
+    pub fn create_chest(&mut self, chest_id: u32, rows: usize, title: Option<String>) -> bool {
+        let chest_type = ContainerType::Chest(chest_id);
+        if self.containers.contains_key(&chest_type) || !(1..=MAX_CHEST_ROWS).contains(&rows) {
+            return false;
+        }
+
+        self.containers.insert(chest_type, SlotContainer::new(rows * CHEST_COLUMNS));
+        if !self.available_chests.contains(&chest_id) {
+            self.available_chests.push(chest_id);
+        }
+        self.chest_info.insert(chest_id, ChestInfo { title, linked_chest: None });
+        true
+    }
+
+    /// Creates two standard chests linked into a double chest, `first_id` being the top half
+    pub fn create_double_chest(&mut self, first_id: u32, second_id: u32, title: Option<String>) -> bool {
+        let is_free = |chest_id| !self.containers.contains_key(&ContainerType::Chest(chest_id));
+        if first_id == second_id || !is_free(first_id) || !is_free(second_id) {
+            return false;
+        }
+
+        self.create_chest(first_id, CHEST_ROWS, title);
+        self.create_chest(second_id, CHEST_ROWS, None);
+        self.link_chests(first_id, second_id)
+    }
+
+    /// Links two existing chests that aren't part of a double chest yet, the lower ID is shown on top
+    pub fn link_chests(&mut self, first_id: u32, second_id: u32) -> bool {
+        let exists = |chest_id| self.containers.contains_key(&ContainerType::Chest(chest_id));
+        let is_linked = |chest_id| self.chest_info.get(&chest_id).is_some_and(|info| info.linked_chest.is_some());
 
+        if first_id == second_id || !exists(first_id) || !exists(second_id) || is_linked(first_id) || is_linked(second_id) {
+            return false;
+        }
+
+        self.chest_info.entry(first_id).or_default().linked_chest = Some(second_id);
+        self.chest_info.entry(second_id).or_default().linked_chest = Some(first_id);
+        true
+    }
+
+    /// Splits a double chest back into two chests, returning the other half
+    pub fn unlink_chest(&mut self, chest_id: u32) -> Option<u32> {
+        let other_id = self.chest_info.get_mut(&chest_id)?.linked_chest.take()?;
+        if let Some(other) = self.chest_info.get_mut(&other_id) {
+            other.linked_chest = None;
+        }
+        Some(other_id)
+    }
+
+    /// The halves of the chest a chest belongs to, top half first, the second is `None` for single chests
+    pub fn chest_halves(&self, chest_id: u32) -> (u32, Option<u32>) {
+        match self.chest_info.get(&chest_id).and_then(|info| info.linked_chest) {
+            Some(other_id) => (chest_id.min(other_id), Some(chest_id.max(other_id))),
+            None => (chest_id, None),
+        }
+    }
+
+    /// Both halves of a double chest, or just the chest itself
+    fn chest_containers(&self, chest_id: u32) -> Vec<ContainerType> {
+        let (first_id, second_id) = self.chest_halves(chest_id);
+        [Some(first_id), second_id].into_iter().flatten().map(ContainerType::Chest).collect()
+    }
+
+    /// The layout of a chest at its actual size, spanning both halves of a double chest
+    pub fn chest_layout(&self, chest_id: u32) -> ContainerLayout {
+        let (first_id, second_id) = self.chest_halves(chest_id);
+        let slot_count = |chest_id| self.get_container(&ContainerType::Chest(chest_id))
+            .map_or(CHEST_ROWS * CHEST_COLUMNS, SlotContainer::len);
+
+        let linked = second_id.map(|second_id| (ContainerType::Chest(second_id), slot_count(second_id)));
+        let total_slots = slot_count(first_id) + linked.as_ref().map_or(0, |(_, linked_count)| *linked_count);
+
+        let title = [Some(first_id), second_id].into_iter().flatten()
+            .find_map(|chest_id| self.chest_info.get(&chest_id).and_then(|info| info.title.clone()))
+            .unwrap_or_else(|| match second_id {
+                Some(_) => format!("Large Chest {}", first_id),
+                None => format!("Chest {}", first_id),
+            });
+
+        ContainerLayout {
+            container_type: ContainerType::Chest(first_id),
+            slot_count: slot_count(first_id),
+            rows: total_slots / CHEST_COLUMNS + usize::from(total_slots % CHEST_COLUMNS != 0),
+            columns: CHEST_COLUMNS,
+            title,
+            position: ContainerPosition::Top,
+            linked,
+        }
+    }
+
+    pub fn open_chest(&mut self, chest_id: u32) {
+        // Create chest if it doesn't exist
+        self.create_chest(chest_id, CHEST_ROWS, None);
+
+        self.return_crafting_grids();
         self.active_chest_id = Some(chest_id);
         self.ui_mode = UIMode::ChestOpen(chest_id);
         self.layouts = vec![
-            ContainerLayout::chest(chest_id),
+            self.chest_layout(chest_id),
+            ContainerLayout::equipment(),
             ContainerLayout::player_inventory(),
             ContainerLayout::hotbar(),
         ];
@@ -144,12 +473,173 @@ impl ContainerManager {
         self.layouts = vec![ContainerLayout::hotbar()];
     }
 
+    pub fn open_furnace(&mut self, furnace_id: u32) {
+        // Create furnace if it doesn't exist
+        self.containers.entry(ContainerType::Furnace(furnace_id))
+            .or_insert_with(|| ContainerType::Furnace(furnace_id).create_container(FURNACE_SLOT_COUNT));
+
+        self.return_crafting_grids();
+        self.active_chest_id = None;
+        self.ui_mode = UIMode::FurnaceOpen(furnace_id);
+        self.layouts = vec![
+            ContainerLayout::furnace(furnace_id),
+            ContainerLayout::equipment(),
+            ContainerLayout::player_inventory(),
+            ContainerLayout::hotbar(),
+        ];
+    }
+
+    pub fn close_furnace(&mut self) {
+        self.close_inventory();
+    }
+
+    /// Opens the container `id` of a registered kind, creating it if it doesn't exist yet
+    ///
+    /// Fails without changing anything if `kind` isn't registered in `container_kinds`.
+    pub fn open_custom(&mut self, container_kinds: &ContainerKindRegistry, kind: ContainerKind, id: u32) -> Result<(), ContainerKindError> {
+        container_kinds.check(&kind)?;
+
+        self.containers.entry(ContainerType::Custom(kind, id))
+            .or_insert_with(|| kind.create_container().with_nestable_storage(container_kinds.nestable_storage(&kind)));
+
+        self.return_crafting_grids();
+        self.active_chest_id = None;
+        self.ui_mode = UIMode::CustomOpen(kind, id);
+        self.layouts = vec![
+            kind.layout(id),
+            ContainerLayout::equipment(),
+            ContainerLayout::player_inventory(),
+            ContainerLayout::hotbar(),
+        ];
+        Ok(())
+    }
+
+    pub fn close_custom(&mut self) {
+        self.close_inventory();
+    }
+
+    /// Shows or hides the contents of the portable container carried by the stack in a slot
+    ///
+    /// The contents get an ID the first time they're opened, which is stored in the stack. They are
+    /// shown as an extra layout next to the open containers (opening the inventory from the hotbar),
+    /// only one portable container is shown at a time. Returns `false` if the slot holds no such item,
+    /// or if the item's `storage` isn't a registered portable kind.
+    pub fn open_portable(&mut self, container_kinds: &ContainerKindRegistry, container_type: &ContainerType, slot_index: usize) -> bool {
+        let Some(stack) = self.get_container(container_type).and_then(|container| container.get_slot(slot_index)).cloned() else { return false; };
+        let Some(kind) = stack.item.and_then(|item| container_kinds.storage_kind(&item)) else { return false; };
+
+        let id = stack.contents.unwrap_or_else(|| self.next_custom_id(kind));
+        if let Some(mut stack) = self.get_container_mut(container_type).and_then(|container| container.get_slot_mut(slot_index)) {
+            stack.contents = Some(id);
+        }
+        self.containers.entry(ContainerType::Custom(kind, id))
+            .or_insert_with(|| kind.create_container().with_nestable_storage(container_kinds.nestable_storage(&kind)));
+
+        let contents_type = ContainerType::Custom(kind, id);
+        let was_open = self.layouts.iter().any(|layout| layout.container_type == contents_type);
+        self.layouts.retain(|layout| !matches!(layout.container_type, ContainerType::Custom(kind, _) if kind.is_portable()));
+
+        self.portable_origin = None;
+        if !was_open {
+            if self.ui_mode == UIMode::HotbarOnly {
+                self.open_inventory();
+            }
+            self.layouts.insert(0, kind.layout(id));
+            self.portable_origin = Some((container_type.clone(), slot_index));
+        }
+
+        true
+    }
+
+    /// Hides the shown portable container once its stack left the slot it was opened from
+    ///
+    /// Moving, dropping or swapping the stack all close it, returns `true` if it was closed.
+    pub fn close_moved_portable(&mut self) -> bool {
+        let shown = self.layouts.iter().find_map(|layout| match layout.container_type {
+            ContainerType::Custom(kind, id) if kind.is_portable() => Some((kind, id)),
+            _ => None,
+        });
+        let Some((kind, id)) = shown else {
+            self.portable_origin = None;
+            return false;
+        };
+
+        let still_there = self.portable_origin.as_ref()
+            .and_then(|(container_type, slot_index)| self.get_container(container_type)?.get_slot(*slot_index))
+            .is_some_and(|stack| stack.contents == Some(id) && stack.item.and_then(|item| item.properties.storage) == Some(kind.identifier));
+        if still_there {
+            return false;
+        }
+
+        self.layouts.retain(|layout| !matches!(layout.container_type, ContainerType::Custom(kind, _) if kind.is_portable()));
+        self.portable_origin = None;
+        true
+    }
+
+    /// Removes portable containers whose ID none of `stacks` carries, returns how many were removed
+    ///
+    /// `stacks` has to cover every stack that still exists: in containers (those are checked here),
+    /// on the cursor, on the ground, waiting to be dropped and in the undo history.
+    pub fn remove_orphaned_portables<'a>(&mut self, stacks: impl IntoIterator<Item = &'a ItemStack>) -> usize {
+        let mut orphaned: std::collections::HashSet<(&'static str, u32)> = self.containers.keys()
+            .filter_map(|container_type| match container_type {
+                ContainerType::Custom(kind, id) if kind.is_portable() => Some((kind.identifier, *id)),
+                _ => None,
+            })
+            .collect();
+        if orphaned.is_empty() {
+            return 0;
+        }
+
+        let mut keep = |stack: &ItemStack| {
+            if let (Some(storage), Some(id)) = (stack.item.and_then(|item| item.properties.storage), stack.contents) {
+                orphaned.remove(&(storage, id));
+            }
+        };
+        for container in self.containers.values() {
+            (0..container.len()).filter_map(|index| container.get_slot(index)).for_each(&mut keep);
+        }
+        stacks.into_iter().for_each(keep);
+
+        if orphaned.is_empty() {
+            return 0;
+        }
+
+        self.containers.retain(|container_type, _| match container_type {
+            ContainerType::Custom(kind, id) => !orphaned.contains(&(kind.identifier, *id)),
+            _ => true,
+        });
+        orphaned.len()
+    }
+
+    /// The lowest ID above every existing container of a kind
+    fn next_custom_id(&self, kind: ContainerKind) -> u32 {
+        self.containers.keys()
+            .filter_map(|container_type| match container_type {
+                ContainerType::Custom(existing, id) if *existing == kind => Some(*id),
+                _ => None,
+            })
+            .max()
+            .map_or(1, |id| id + 1)
+    }
+
     pub fn switch_chest(&mut self, chest_id: u32) {
         if self.available_chests.contains(&chest_id) {
             self.open_chest(chest_id);
         }
     }
 
+    /// The containers opened by the current UI mode, besides the player's own ones
+    pub fn open_containers(&self) -> Vec<ContainerType> {
+        match self.ui_mode {
+            UIMode::ChestOpen(chest_id) => self.chest_containers(chest_id),
+            UIMode::CraftingTableOpen(table_id) => vec![ContainerType::CraftingGrid(CraftingStation::Table(table_id))],
+            UIMode::FurnaceOpen(furnace_id) => vec![ContainerType::Furnace(furnace_id)],
+            UIMode::CustomOpen(kind, id) => vec![ContainerType::Custom(kind, id)],
+            UIMode::HotbarOnly | UIMode::InventoryOpen => Vec::new(),
+        }
+    }
+
     pub fn get_container(&self, container_type: &ContainerType) -> Option<&SlotContainer> {
         self.containers.get(container_type)
     }
@@ -157,12 +647,38 @@ impl ContainerManager {
     pub fn get_container_mut(&mut self, container_type: &ContainerType) -> Option<&mut SlotContainer> {
         self.containers.get_mut(container_type)
     }
+
+    /// Sorts and consolidates a container, returns `false` if it wasn't sorted
+    ///
+    /// The hotbar is only sorted when `SortSettings::include_hotbar` is set. Both halves of a
+    /// double chest are sorted, each on its own.
+    pub fn sort_container(&mut self, container_type: &ContainerType, settings: &SortSettings) -> bool {
+        if !container_type.is_sortable() || (*container_type == ContainerType::Hotbar && !settings.include_hotbar) {
+            return false;
+        }
+
+        let container_types = match container_type {
+            ContainerType::Chest(chest_id) => self.chest_containers(*chest_id),
+            _ => vec![container_type.clone()],
+        };
+
+        container_types.iter().fold(false, |sorted, container_type| {
+            let sorted_this = self.get_container_mut(container_type)
+                .is_some_and(|container| sort_container(container, &settings.order));
+            sorted || sorted_this
+        })
+    }
+
+    /// Wears down the stack in a container slot, returning the item if it broke
+    pub fn damage_slot(&mut self, container_type: &ContainerType, slot_index: usize, amount: u128) -> Option<Item> {
+        self.get_container_mut(container_type)?.damage_slot(slot_index, amount)
+    }
 }
 
 // Component to mark UI elements as belonging to a specific container
 #[derive(Component)]
 pub struct ContainerUI {
-    #[allow(dead_code)] pub container_type: ContainerType, // todo maybe remove? could be useful? who knows
+    pub layout: ContainerLayout, // the layout the panel's slots currently show, see `sync_container_ui`
 }
 
 // Events for container switching
@@ -183,4 +699,103 @@ pub struct CloseChestEvent;
 #[derive(Event)]
 pub struct SwitchChestEvent {
     pub chest_id: u32,
-}
\ No newline at end of file
+}
+
+#[derive(Event)]
+pub struct OpenCraftingTableEvent {
+    pub table_id: u32,
+}
+
+#[derive(Event)]
+pub struct CloseCraftingTableEvent;
+
+#[derive(Event)]
+pub struct OpenFurnaceEvent {
+    pub furnace_id: u32,
+}
+
+#[derive(Event)]
+pub struct CloseFurnaceEvent;
+
+#[derive(Event)]
+pub struct OpenCustomContainerEvent {
+    pub kind: ContainerKind,
+    pub id: u32,
+}
+
+#[derive(Event)]
+pub struct CloseCustomContainerEvent;
+
+#[derive(Event)]
+pub struct OpenPortableContainerEvent {
+    pub container_type: ContainerType,
+    pub slot_index: usize,
+}
+// Events for item durability
+#[derive(Event)]
+pub struct DamageItemEvent {
+    pub container_type: ContainerType,
+    pub slot_index: usize,
+    pub amount: u128,
+}
+
+#[derive(Event)]
+pub struct ItemBrokenEvent {
+    pub container_type: ContainerType,
+    pub slot_index: usize,
+    pub item: Item,
+}
+
+
+// Events for slot changes, sent after the change happened
+// `before` and `after` are equal when only the slot's lock was toggled
+#[derive(Event, Debug, Clone)]
+pub struct SlotChangedEvent {
+    pub container: ContainerType,
+    pub index: usize,
+    pub before: Option<ItemStack>,
+    pub after: Option<ItemStack>,
+}
+
+/// Items taken out of a slot onto the cursor
+#[derive(Event, Debug, Clone)]
+pub struct ItemPickedUpEvent {
+    pub container: ContainerType,
+    pub index: usize,
+    pub stack: ItemStack,
+}
+
+/// Items placed into a slot that was empty (or held a different item)
+#[derive(Event, Debug, Clone)]
+pub struct ItemDepositedEvent {
+    pub container: ContainerType,
+    pub index: usize,
+    pub stack: ItemStack,
+}
+
+/// Items added onto an existing stack of the same item
+#[derive(Event, Debug, Clone)]
+pub struct StackMergedEvent {
+    pub container: ContainerType,
+    pub index: usize,
+    pub item: Item,
+    pub amount: u32,
+    pub new_size: u32,
+}
+
+#[derive(Event, Debug, Clone)]
+pub struct ItemsCraftedEvent {
+    pub station: CraftingStation,
+    pub item: Item,
+    pub count: u32,
+}
+
+/// A consumable was used up, gameplay systems apply its `effects`
+#[derive(Event, Debug, Clone)]
+pub struct ItemConsumedEvent {
+    pub container: ContainerType,
+    pub index: usize,
+    pub item: Item,
+    pub effects: &'static [ConsumeEffect],
+    pub remainder: Option<Item>,
+}
@@ -0,0 +1,19 @@
+use crate::world::item::item::EquipmentSlot;
+
+// Slot layout of the player's equipment container
+pub const EQUIPMENT_HEAD_SLOT: usize = 0;
+pub const EQUIPMENT_CHEST_SLOT: usize = 1;
+pub const EQUIPMENT_LEGS_SLOT: usize = 2;
+pub const EQUIPMENT_FEET_SLOT: usize = 3;
+pub const EQUIPMENT_OFFHAND_SLOT: usize = 4;
+pub const EQUIPMENT_SLOT_COUNT: usize = 5;
+
+/// The equipment container slot an armour piece goes into
+pub fn equipment_slot_index(slot: EquipmentSlot) -> usize {
+    match slot {
+        EquipmentSlot::Head => EQUIPMENT_HEAD_SLOT,
+        EquipmentSlot::Chest => EQUIPMENT_CHEST_SLOT,
+        EquipmentSlot::Legs => EQUIPMENT_LEGS_SLOT,
+        EquipmentSlot::Feet => EQUIPMENT_FEET_SLOT,
+    }
+}
@@ -0,0 +1,37 @@
+use crate::world::inventory::item_stack::ItemStack;
+use crate::world::item::item::EquipmentSlot;
+
+/// What a slot accepts, checked by every operation that puts items into a slot
+///
+/// Filters only restrict insertion, items can always be taken out of a slot.
+#[derive(Debug, Copy, Clone, Default)]
+pub enum SlotFilter {
+    #[default]
+    Any,
+    OutputOnly,       // take-only, like crafting results and furnace outputs
+    OffhandEquipable, // items with `offhand_equipable`
+    Fuel,             // items with a `fuel_value`
+    Smeltable,        // items with a `smelt_result`
+    Equipment(EquipmentSlot), // armour worn in this slot
+    Custom(fn(&ItemStack) -> bool),
+}
+
+impl SlotFilter {
+    pub fn accepts(&self, stack: &ItemStack) -> bool {
+        let Some(item) = stack.item else { return false; };
+
+        match self {
+            SlotFilter::Any => true,
+            SlotFilter::OutputOnly => false,
+            SlotFilter::OffhandEquipable => item.properties.offhand_equipable,
+            SlotFilter::Fuel => item.properties.fuel_value > 0,
+            SlotFilter::Smeltable => item.properties.smelt_result.is_some(),
+            SlotFilter::Equipment(slot) => item.properties.equipment_slot == Some(*slot),
+            SlotFilter::Custom(predicate) => predicate(stack),
+        }
+    }
+
+    pub fn is_output_only(&self) -> bool {
+        matches!(self, SlotFilter::OutputOnly)
+    }
+}
@@ -0,0 +1,143 @@
+use crate::world::inventory::inventory::SlotContainer;
+use crate::world::inventory::item_stack::ItemStack;
+use crate::world::item::registry::ItemRegistry;
+use bevy::prelude::Resource;
+use std::collections::HashMap;
+
+// Slot layout of every furnace container
+pub const FURNACE_INPUT_SLOT: usize = 0;
+pub const FURNACE_FUEL_SLOT: usize = 1;
+pub const FURNACE_OUTPUT_SLOT: usize = 2;
+pub const FURNACE_SLOT_COUNT: usize = 3;
+
+/// Seconds it takes to smelt a single item
+pub const SMELT_TIME_SECS: f32 = 5.0;
+
+/// Burn and cook progress of a single furnace
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FurnaceState {
+    pub burn_time_left: f32,
+    pub burn_time_total: f32, // burn time of the fuel item currently burning
+    pub cook_time: f32,
+}
+
+impl FurnaceState {
+    pub fn is_burning(&self) -> bool {
+        self.burn_time_left > 0.0
+    }
+
+    /// Remaining fuel of the current item, from 1.0 (just lit) to 0.0
+    pub fn burn_fraction(&self) -> f32 {
+        if self.burn_time_total <= 0.0 {
+            0.0
+        } else {
+            (self.burn_time_left / self.burn_time_total).clamp(0.0, 1.0)
+        }
+    }
+
+    /// Progress of the item currently smelting, from 0.0 to 1.0
+    pub fn cook_fraction(&self) -> f32 {
+        (self.cook_time / SMELT_TIME_SECS).clamp(0.0, 1.0)
+    }
+
+    /// Advances the furnace by `delta` seconds, consuming fuel and smelting items in `container`
+    ///
+    /// A long `delta` smelts as many items (and burns as much fuel) as fit into it, so progress
+    /// doesn't depend on the frame rate. Returns `true` if any slot of the container changed.
+    pub fn tick(&mut self, mut delta: f32, container: &mut SlotContainer, item_registry: &ItemRegistry) -> bool {
+        let mut changed = false;
+
+        while delta > 0.0 {
+            let output = smelt_output(container, item_registry);
+
+            // light the next fuel item only when there's something to smelt
+            if !self.is_burning() && output.is_some() {
+                if let Some(fuel_value) = fuel_value(container) {
+                    consume_one(container, FURNACE_FUEL_SLOT);
+                    changed = true;
+
+                    self.burn_time_total = fuel_value as f32 * SMELT_TIME_SECS;
+                    self.burn_time_left = self.burn_time_total;
+                }
+            }
+
+            if !self.is_burning() {
+                self.cook_time = 0.0;
+                break;
+            }
+
+            let Some(output) = output else {
+                self.burn_time_left = (self.burn_time_left - delta).max(0.0);
+                self.cook_time = 0.0;
+                break;
+            };
+
+            // up to whichever runs out first: the time left, the fuel or the current item
+            let step = delta.min(self.burn_time_left).min((SMELT_TIME_SECS - self.cook_time).max(0.0));
+            delta -= step;
+            self.burn_time_left = (self.burn_time_left - step).max(0.0);
+            self.cook_time += step;
+
+            if self.cook_time >= SMELT_TIME_SECS {
+                self.cook_time = 0.0;
+                consume_one(container, FURNACE_INPUT_SLOT);
+                let _ = container.set_slot(FURNACE_OUTPUT_SLOT, Some(output));
+                changed = true;
+            }
+        }
+
+        changed
+    }
+
+    /// Checks if there is no progress worth keeping, such states aren't saved
+    pub fn is_idle(&self) -> bool {
+        !self.is_burning() && self.cook_time <= 0.0
+    }
+}
+
+/// Burn and cook progress of every furnace, keyed by furnace ID
+///
+/// Kept apart from `ContainerManager` so furnaces keep working while their UI is closed.
+#[derive(Resource, Default)]
+pub struct Furnaces {
+    pub states: HashMap<u32, FurnaceState>,
+}
+
+impl Furnaces {
+    pub fn get(&self, furnace_id: u32) -> Option<&FurnaceState> {
+        self.states.get(&furnace_id)
+    }
+}
+
+/// The output slot contents after smelting one more input item, if the furnace can smelt right now
+fn smelt_output(container: &SlotContainer, item_registry: &ItemRegistry) -> Option<ItemStack> {
+    let input = container.get_slot(FURNACE_INPUT_SLOT)?;
+    let result = item_registry.get(input.item?.properties.smelt_result?)?;
+
+    match container.get_slot(FURNACE_OUTPUT_SLOT) {
+        None => Some(ItemStack::new(result, 1)),
+        Some(output) => {
+            let smelted = ItemStack::new(result, 1);
+            if output.can_merge_with(&smelted) && output.size < result.properties.max_stack_size {
+                Some(output.with_size(output.size + 1))
+            } else {
+                None
+            }
+        }
+    }
+}
+
+fn fuel_value(container: &SlotContainer) -> Option<u32> {
+    let fuel = container.get_slot(FURNACE_FUEL_SLOT)?;
+    let fuel_value = fuel.item?.properties.fuel_value;
+    (fuel_value > 0).then_some(fuel_value)
+}
+
+fn consume_one(container: &mut SlotContainer, slot_index: usize) {
+    let Some(mut stack) = container.get_slot_mut(slot_index) else { return; };
+
+    stack.size -= 1;
+    if stack.size == 0 {
+        container.take_slot(slot_index);
+    }
+}
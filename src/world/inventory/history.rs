@@ -0,0 +1,315 @@
+use crate::world::inventory::components::HeldItem;
+use crate::world::inventory::containers::{ContainerManager, ContainerType};
+use crate::world::inventory::item_stack::ItemStack;
+use bevy::prelude::{error, Resource};
+use std::collections::VecDeque;
+use std::fmt;
+
+/// How many transactions `InventoryHistory` keeps by default
+pub const DEFAULT_HISTORY_CAPACITY: usize = 100;
+
+/// The contents of one slot before and after a transaction
+#[derive(Debug, Clone, PartialEq)]
+pub struct SlotChange {
+    pub container_type: ContainerType,
+    pub slot_index: usize,
+    pub before: Option<ItemStack>,
+    pub after: Option<ItemStack>,
+}
+
+/// A reversible inventory operation: every slot it touched, plus the cursor
+#[derive(Debug, Clone, PartialEq)]
+pub struct InventoryTransaction {
+    pub changes: Vec<SlotChange>,
+    pub held_before: Option<ItemStack>,
+    pub held_after: Option<ItemStack>,
+}
+
+impl InventoryTransaction {
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty() && self.held_before == self.held_after
+    }
+
+    /// The transaction that reverts this one
+    pub fn inverted(&self) -> Self {
+        Self {
+            changes: self.changes.iter()
+                .map(|change| SlotChange {
+                    container_type: change.container_type.clone(),
+                    slot_index: change.slot_index,
+                    before: change.after.clone(),
+                    after: change.before.clone(),
+                })
+                .collect(),
+            held_before: self.held_after.clone(),
+            held_after: self.held_before.clone(),
+        }
+    }
+
+    /// Folds a transaction that happened after this one into it, so both are undone as one
+    ///
+    /// Slots touched by both keep this transaction's `before` and take the later `after`.
+    pub fn merge(&mut self, later: InventoryTransaction) {
+        for change in later.changes {
+            let earlier = self.changes.iter_mut()
+                .find(|earlier| earlier.container_type == change.container_type && earlier.slot_index == change.slot_index);
+
+            match earlier {
+                Some(earlier) => earlier.after = change.after,
+                None => self.changes.push(change),
+            }
+        }
+
+        self.changes.retain(|change| change.before != change.after);
+        self.changes.sort_by(|a, b| (&a.container_type, a.slot_index).cmp(&(&b.container_type, b.slot_index)));
+        self.held_after = later.held_after;
+    }
+
+    /// Checks that the inventory still looks like this transaction's `after` (or `before`) side
+    fn matches(&self, container_manager: &ContainerManager, held_item: &HeldItem, after: bool) -> bool {
+        let held_expected = if after { &self.held_after } else { &self.held_before };
+        if held_item.stack != *held_expected {
+            return false;
+        }
+
+        self.changes.iter().all(|change| {
+            let expected = if after { &change.after } else { &change.before };
+            container_manager.get_container(&change.container_type)
+                .is_some_and(|container| container.get_slot(change.slot_index) == expected.as_ref())
+        })
+    }
+
+    fn apply(&self, container_manager: &mut ContainerManager, held_item: &mut HeldItem, after: bool) {
+        for change in &self.changes {
+            let stack = if after { change.after.clone() } else { change.before.clone() };
+            if let Some(container) = container_manager.get_container_mut(&change.container_type) {
+                let _ = container.set_slot(change.slot_index, stack);
+            }
+        }
+
+        held_item.stack = if after { self.held_after.clone() } else { self.held_before.clone() };
+    }
+}
+
+/// The revision of every container and the cursor when a transaction began, see `InventoryHistory::begin`
+///
+/// The containers journal each slot before it's written, so only the slots that were written get compared.
+/// They keep journaling until the transaction is finished, dropping one unfinished is a bug and logged as such.
+#[must_use = "a pending transaction has to be committed, rolled back or discarded"]
+pub struct PendingTransaction {
+    revisions: Vec<(ContainerType, u64)>,
+    held: Option<ItemStack>,
+}
+
+impl PendingTransaction {
+    fn capture(container_manager: &mut ContainerManager, held_item: &HeldItem) -> Self {
+        let revisions = container_manager.containers.iter_mut()
+            .filter(|(container_type, _)| is_recorded(container_type))
+            .map(|(container_type, container)| (container_type.clone(), container.start_recording()))
+            .collect();
+
+        Self {
+            revisions,
+            held: held_item.stack.clone(),
+        }
+    }
+
+    /// Diffs the journaled slots against their current contents
+    fn finish(mut self, container_manager: &mut ContainerManager, held_item: &HeldItem) -> InventoryTransaction {
+        let mut changes = Vec::new();
+
+        // taking the revisions marks the transaction as finished for `drop`
+        for (container_type, since) in std::mem::take(&mut self.revisions) {
+            let Some(container) = container_manager.get_container_mut(&container_type) else { continue; };
+
+            for (slot_index, before) in container.finish_recording(since) {
+                let after = container.get_slot(slot_index).cloned();
+                if before != after {
+                    changes.push(SlotChange { container_type: container_type.clone(), slot_index, before, after });
+                }
+            }
+        }
+
+        // HashMap order is random, keep transactions deterministic
+        changes.sort_by(|a, b| (&a.container_type, a.slot_index).cmp(&(&b.container_type, b.slot_index)));
+
+        InventoryTransaction {
+            changes,
+            held_before: self.held.take(),
+            held_after: held_item.stack.clone(),
+        }
+    }
+}
+
+impl Drop for PendingTransaction {
+    fn drop(&mut self) {
+        if !self.revisions.is_empty() && !std::thread::panicking() {
+            error!("an inventory transaction was dropped without being finished, its containers keep journaling every write");
+        }
+    }
+}
+
+/// Crafting outputs are recomputed from their grid, undoing the grid is enough
+fn is_recorded(container_type: &ContainerType) -> bool {
+    !matches!(container_type, ContainerType::CraftingOutput(_))
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HistoryError {
+    NothingToUndo,
+    NothingToRedo,
+    /// The slots were changed by something outside the history (e.g. a furnace) since the transaction
+    Conflict,
+}
+
+impl fmt::Display for HistoryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HistoryError::NothingToUndo => write!(f, "nothing to undo"),
+            HistoryError::NothingToRedo => write!(f, "nothing to redo"),
+            HistoryError::Conflict => write!(f, "the inventory changed since this operation, it can't be reverted"),
+        }
+    }
+}
+
+impl std::error::Error for HistoryError {}
+
+/// Bounded undo/redo history of inventory transactions
+///
+/// Operations are recorded by wrapping them in `begin` / `commit`:
+/// ```ignore
+/// let transaction = history.begin(&mut container_manager, &held_item);
+/// process_left_click(slot_index, container, &mut held_item);
+/// history.commit(transaction, &mut container_manager, &held_item);
+/// ```
+#[derive(Resource)]
+pub struct InventoryHistory {
+    undo_stack: VecDeque<InventoryTransaction>,
+    redo_stack: Vec<InventoryTransaction>,
+    capacity: usize,
+}
+
+impl Default for InventoryHistory {
+    fn default() -> Self {
+        Self::with_capacity(DEFAULT_HISTORY_CAPACITY)
+    }
+}
+
+impl InventoryHistory {
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            undo_stack: VecDeque::new(),
+            redo_stack: Vec::new(),
+            capacity,
+        }
+    }
+
+    /// Starts recording the changes made until `commit` (or `rollback`, or `discard`)
+    ///
+    /// Every pending transaction must be handed to one of those, the containers keep journaling
+    /// their writes until it is.
+    pub fn begin(&self, container_manager: &mut ContainerManager, held_item: &HeldItem) -> PendingTransaction {
+        PendingTransaction::capture(container_manager, held_item)
+    }
+
+    /// Records everything that changed since `begin` and returns it, `None` if nothing did
+    ///
+    /// Recording a new transaction clears the redo stack.
+    pub fn commit(&mut self, pending: PendingTransaction, container_manager: &mut ContainerManager, held_item: &HeldItem) -> Option<InventoryTransaction> {
+        let transaction = pending.finish(container_manager, held_item);
+        self.push(transaction.clone()).then_some(transaction)
+    }
+
+    /// Reverts everything that changed since `begin` without recording it
+    pub fn rollback(&self, pending: PendingTransaction, container_manager: &mut ContainerManager, held_item: &mut HeldItem) {
+        let transaction = pending.finish(container_manager, held_item);
+        transaction.apply(container_manager, held_item, false);
+    }
+
+    /// Returns everything that changed since `begin` without recording it
+    ///
+    /// For changes that can't be undone, like consuming or dropping items.
+    pub fn discard(&self, pending: PendingTransaction, container_manager: &mut ContainerManager, held_item: &HeldItem) -> InventoryTransaction {
+        pending.finish(container_manager, held_item)
+    }
+
+    /// Records an already built transaction, returns `false` if it is empty
+    pub fn push(&mut self, transaction: InventoryTransaction) -> bool {
+        if transaction.is_empty() {
+            return false;
+        }
+
+        self.redo_stack.clear();
+        self.undo_stack.push_back(transaction);
+        while self.undo_stack.len() > self.capacity {
+            self.undo_stack.pop_front();
+        }
+        true
+    }
+
+    /// Reverts the most recent transaction, returning the changes that were made to do so
+    ///
+    /// If the affected slots were changed by something else in the meantime the transaction
+    /// is dropped and `HistoryError::Conflict` returned, nothing is modified.
+    pub fn undo(&mut self, container_manager: &mut ContainerManager, held_item: &mut HeldItem) -> Result<InventoryTransaction, HistoryError> {
+        let transaction = self.undo_stack.pop_back().ok_or(HistoryError::NothingToUndo)?;
+
+        if !transaction.matches(container_manager, held_item, true) {
+            return Err(HistoryError::Conflict);
+        }
+
+        transaction.apply(container_manager, held_item, false);
+        let applied = transaction.inverted();
+        self.redo_stack.push(transaction);
+        Ok(applied)
+    }
+
+    /// Re-applies the most recently undone transaction, returning it
+    pub fn redo(&mut self, container_manager: &mut ContainerManager, held_item: &mut HeldItem) -> Result<InventoryTransaction, HistoryError> {
+        let transaction = self.redo_stack.pop().ok_or(HistoryError::NothingToRedo)?;
+
+        if !transaction.matches(container_manager, held_item, false) {
+            return Err(HistoryError::Conflict);
+        }
+
+        transaction.apply(container_manager, held_item, true);
+        self.undo_stack.push_back(transaction.clone());
+        Ok(transaction)
+    }
+
+    /// Undoes up to `count` transactions, stopping at the first error
+    pub fn rollback_last(&mut self, count: usize, container_manager: &mut ContainerManager, held_item: &mut HeldItem) -> Result<(), HistoryError> {
+        for _ in 0..count {
+            self.undo(container_manager, held_item)?;
+        }
+        Ok(())
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.undo_stack.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo_stack.is_empty()
+    }
+
+    /// Every recorded transaction, oldest first
+    pub fn transactions(&self) -> impl Iterator<Item = &InventoryTransaction> {
+        self.undo_stack.iter()
+    }
+
+    /// Every stack undo or redo could bring back
+    pub fn stacks(&self) -> impl Iterator<Item = &ItemStack> {
+        self.undo_stack.iter().chain(&self.redo_stack)
+            .flat_map(|transaction| {
+                let held = [&transaction.held_before, &transaction.held_after];
+                let slots = transaction.changes.iter().flat_map(|change| [&change.before, &change.after]);
+                held.into_iter().chain(slots).flatten()
+            })
+    }
+
+    pub fn clear(&mut self) {
+        self.undo_stack.clear();
+        self.redo_stack.clear();
+    }
+}
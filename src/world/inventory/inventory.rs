@@ -1,12 +1,24 @@
+use crate::world::inventory::filter::SlotFilter;
 use crate::world::inventory::item_stack::ItemStack;
+use crate::world::item::item::Item;
 use bevy::prelude::Resource;
+use std::collections::BTreeMap;
+use std::ops::{Deref, DerefMut, Range};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Source of every container's revisions, shared so a replaced container never repeats one
+static NEXT_REVISION: AtomicU64 = AtomicU64::new(1);
+
+fn next_revision() -> u64 {
+    NEXT_REVISION.fetch_add(1, Ordering::Relaxed)
+}
 
 #[derive(Debug, Clone)]
 pub enum InventoryError {
     IndexOutOfBounds { index: usize, max_size: usize },
 }
 
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Clone)]
 pub struct Slot {
     pub stack: Option<ItemStack>
 }
@@ -39,7 +51,7 @@ impl Slot {
     #[allow(unused)]
     pub fn insert(&mut self, stack: ItemStack) -> Option<ItemStack> {
         match &mut self.stack {
-            Some(existing) if existing.item == stack.item => {
+            Some(existing) if existing.can_merge_with(&stack) => {
                 let max = existing.item.unwrap().properties.max_stack_size;
                 let available = max.saturating_sub(existing.size);
                 let to_add = available.min(stack.size);
@@ -49,10 +61,7 @@ impl Slot {
                     None
                 }
                 else {
-                    Some(ItemStack {
-                        item: stack.item,
-                        size: stack.size - to_add,
-                    })
+                    Some(stack.with_size(stack.size - to_add))
                 }
             }
             None => {
@@ -66,20 +75,71 @@ impl Slot {
     }
 }
 
+/// Mutable access to the stack in a slot, see `SlotContainer::get_slot_mut`
+///
+/// Reading through it changes nothing, the slot is only marked as written (its revision bumped and
+/// its old contents journaled) the first time it's accessed mutably.
+pub struct SlotMut<'a> {
+    container: &'a mut SlotContainer,
+    index: usize,
+    written: bool,
+}
+
+impl Deref for SlotMut<'_> {
+    type Target = ItemStack;
+
+    fn deref(&self) -> &ItemStack {
+        self.container.slots[self.index].stack.as_ref().expect("get_slot_mut only hands out slots holding a stack")
+    }
+}
+
+impl DerefMut for SlotMut<'_> {
+    fn deref_mut(&mut self) -> &mut ItemStack {
+        if !self.written {
+            self.container.before_write(self.index);
+            self.written = true;
+        }
+        self.container.slots[self.index].stack.as_mut().expect("get_slot_mut only hands out slots holding a stack")
+    }
+}
+
+/// What a slot held before a write, kept while a transaction records the container
+#[derive(Debug, Clone)]
+struct SlotRecord {
+    revision: u64, // the revision the write was made at
+    index: usize,
+    before: Option<ItemStack>,
+}
+
 #[derive(Resource)]
 pub struct SlotContainer {
     #[allow(dead_code)] slot_count: usize, // todo maybe remove? maybe use?
     // slots: [Option<ItemStack>; components::SLOT_COUNT],
     slots: Vec<Slot>, // slot containers may have any variable amount of slots, which each hold an optional item stack
+    filters: Vec<SlotFilter>, // what each slot accepts, same length as `slots`
+    locked: Vec<bool>, // slots sorting leaves alone, same length as `slots`
+    nestable_storage: Option<Vec<&'static str>>, // set for containers carried by items: the `storage` of items they may hold
+    revisions: Vec<u64>, // bumped on every write to a slot, same length as `slots`
+    revision: u64, // the latest of `revisions`
+    journal: Vec<SlotRecord>, // only filled while `recorders` is above zero
+    recorders: usize, // open transactions recording this container, see `start_recording`
 }
 
 impl Default for SlotContainer {
 
     fn default() -> SlotContainer {
+        let revision = next_revision();
         SlotContainer {
             slot_count: 1,
             // slots: Vec::from([Some(ItemStack::new(DIAMOND, 1)); 1]), // slot containers
             slots: Vec::from([Slot::new(ItemStack::empty()); 1]), // slot containers
+            filters: vec![SlotFilter::Any],
+            locked: vec![false],
+            nestable_storage: None,
+            revisions: vec![revision],
+            revision,
+            journal: Vec::new(),
+            recorders: 0,
         }
     }
 }
@@ -87,13 +147,90 @@ impl Default for SlotContainer {
 impl SlotContainer {
 
     pub fn new(slot_count: usize) -> Self {
+        let revision = next_revision();
         Self {
             slot_count,
             // slots: Vec::from([Some(ItemStack::empty()); 1])
-            slots: (0..slot_count).map(|_| Slot::empty()).collect()
+            slots: (0..slot_count).map(|_| Slot::empty()).collect(),
+            filters: vec![SlotFilter::Any; slot_count],
+            locked: vec![false; slot_count],
+            nestable_storage: None,
+            revisions: vec![revision; slot_count],
+            revision,
+            journal: Vec::new(),
+            recorders: 0,
         }
     }
 
+    /// Only accepts items carrying portable containers of the listed kinds, builder-style
+    ///
+    /// See `ContainerKindRegistry::nestable_storage`, `None` accepts any item.
+    pub fn with_nestable_storage(mut self, nestable_storage: Option<Vec<&'static str>>) -> Self {
+        self.nestable_storage = nestable_storage;
+        self
+    }
+
+    /// Restricts what a slot accepts, builder-style
+    pub fn with_slot_filter(mut self, index: usize, filter: SlotFilter) -> Self {
+        let _ = self.set_slot_filter(index, filter);
+        self
+    }
+
+    pub fn set_slot_filter(&mut self, index: usize, filter: SlotFilter) -> Result<(), InventoryError> {
+        let max_size = self.filters.len();
+        let slot_filter = self.filters.get_mut(index).ok_or(InventoryError::IndexOutOfBounds { index, max_size })?;
+        *slot_filter = filter;
+        self.touch(index);
+        Ok(())
+    }
+
+    /// Copies the filters of every slot that exists in both containers
+    pub fn copy_slot_filters_from(&mut self, other: &SlotContainer) {
+        for (filter, other_filter) in self.filters.iter_mut().zip(&other.filters) {
+            *filter = *other_filter;
+        }
+        for index in 0..self.filters.len().min(other.filters.len()) {
+            self.touch(index);
+        }
+    }
+
+    pub fn slot_filter(&self, index: usize) -> SlotFilter {
+        self.filters.get(index).copied().unwrap_or_default()
+    }
+
+    /// Checks if the player may put (some of) `stack` into a slot, ignoring what's already in it
+    pub fn can_insert(&self, index: usize, stack: &ItemStack) -> bool {
+        index < self.slots.len() && self.slot_filter(index).accepts(stack) && self.accepts_nested(stack)
+    }
+
+    /// Keeps portable containers from holding themselves or nesting deeper than their `max_nesting`
+    fn accepts_nested(&self, stack: &ItemStack) -> bool {
+        let Some(nestable_storage) = &self.nestable_storage else { return true; };
+
+        match stack.item.and_then(|item| item.properties.storage) {
+            Some(storage) => nestable_storage.contains(&storage),
+            None => true,
+        }
+    }
+
+    /// Checks if a slot can only be taken from
+    pub fn is_output_slot(&self, index: usize) -> bool {
+        self.slot_filter(index).is_output_only()
+    }
+
+    /// Locks or unlocks a slot, locked slots keep their item when the container is sorted
+    pub fn set_slot_locked(&mut self, index: usize, locked: bool) -> Result<(), InventoryError> {
+        let max_size = self.locked.len();
+        let slot_locked = self.locked.get_mut(index).ok_or(InventoryError::IndexOutOfBounds { index, max_size })?;
+        *slot_locked = locked;
+        self.touch(index);
+        Ok(())
+    }
+
+    pub fn is_slot_locked(&self, index: usize) -> bool {
+        self.locked.get(index).copied().unwrap_or(false)
+    }
+
     /// Gets a reference to the item in a specific slot
     /// "look but don't touch!"
     pub fn get_slot(&self, index: usize) -> Option<&ItemStack> {
@@ -105,21 +242,24 @@ impl SlotContainer {
         self.slots.get(index)?.stack.as_ref()
     }
 
-    /// Gets a mutable reference to the item in a specific slot
-    pub fn get_slot_mut(&mut self, index: usize) -> Option<&mut ItemStack> {
+    /// Gets mutable access to the item in a specific slot
+    pub fn get_slot_mut(&mut self, index: usize) -> Option<SlotMut<'_>> {
 
         // double-optional
         // same thing as above basically
         // with great power, comes great responsibility
+        // but only writing through it counts as a change
 
-        self.slots.get_mut(index)?.stack.as_mut()
+        self.slots.get(index)?.stack.as_ref()?;
+        Some(SlotMut { container: self, index, written: false })
     }
 
     /// Sets the contents of a specific slot
     /// Overrides the original content
     pub fn set_slot(&mut self, index: usize, stack: Option<ItemStack>) -> Result<(), InventoryError> {
-        if let Some(slot) = self.slots.get_mut(index) {
-            *slot = Slot { stack };
+        if index < self.slots.len() {
+            self.before_write(index);
+            self.slots[index] = Slot { stack };
             Ok(())
         } else {
             Err(InventoryError::IndexOutOfBounds { 
@@ -132,17 +272,171 @@ impl SlotContainer {
     /// Sets the contents of a specific slot, panicking on out-of-bounds access
     /// Only use this when you're certain the index is valid
     pub fn set_slot_unchecked(&mut self, index: usize, stack: Option<ItemStack>) {
+        self.before_write(index);
         self.slots[index] = Slot { stack };
     }
 
     /// Removes and returns the item from a specific slot
     pub fn take_slot(&mut self, index: usize) -> Option<ItemStack> {
         // .take() replaces the value with None, and gives use the old value
+        self.before_write(index);
         self.slots.get_mut(index)?.take()
     }
 
+    /// Wears down the stack in a specific slot
+    ///
+    /// If the stack breaks, the slot is cleared and the broken item is returned.
+    pub fn damage_slot(&mut self, index: usize, amount: u128) -> Option<Item> {
+        self.get_slot(index)?.durability?; // items without durability don't wear down
+
+        self.before_write(index);
+        let slot = &mut self.slots[index];
+        let stack = slot.stack.as_mut()?;
+
+        if stack.damage(amount) {
+            let broken = stack.item;
+            slot.stack = None;
+            broken
+        } else {
+            None
+        }
+    }
+
+    /// Repairs the stack in a specific slot, up to the item's maximum durability
+    pub fn repair_slot(&mut self, index: usize, amount: u128) {
+        if let Some(mut stack) = self.get_slot_mut(index) {
+            stack.repair(amount);
+        }
+    }
+
+    /// Merges a stack into matching stacks, then puts what's left into the first empty slot
+    ///
+    /// Slot filters are respected. Returns whatever didn't fit.
+    pub fn try_place_stack(&mut self, stack: ItemStack) -> Option<ItemStack> {
+        let slot_count = self.len();
+        self.try_place_stack_in_slots(stack, 0..slot_count)
+    }
+
+    /// Like `try_place_stack`, but only uses the slots in `slots`
+    pub fn try_place_stack_in_slots(&mut self, mut stack: ItemStack, slots: Range<usize>) -> Option<ItemStack> {
+        let slots = slots.start..slots.end.min(self.len());
+
+        // First pass: try to merge with existing stacks
+        for slot_index in slots.clone() {
+            if !self.can_insert(slot_index, &stack) {
+                continue;
+            }
+
+            if let Some(mut existing_stack) = self.get_slot_mut(slot_index) {
+                if stack.can_merge_with(&existing_stack) {
+                    let max_size = existing_stack.item.unwrap().properties.max_stack_size;
+                    let available_space = max_size.saturating_sub(existing_stack.size);
+                    let to_add = available_space.min(stack.size);
+
+                    existing_stack.size += to_add;
+                    stack.size -= to_add;
+
+                    if stack.size == 0 {
+                        return None; // All items placed
+                    }
+                }
+            }
+        }
+
+        // Second pass: place remaining items in empty slots
+        for slot_index in slots {
+            if self.get_slot(slot_index).is_none() && self.can_insert(slot_index, &stack) {
+                let _ = self.set_slot(slot_index, Some(stack));
+                return None; // All items placed
+            }
+        }
+
+        // Return remaining items if container is full
+        Some(stack)
+    }
+
+    /// How many items of `stack` `try_place_stack` would place, without placing any
+    pub fn room_for(&self, stack: &ItemStack) -> u32 {
+        let Some(item) = stack.item else { return 0; };
+        let max_size = item.properties.max_stack_size;
+        let mut room = 0u32;
+
+        for slot_index in 0..self.len() {
+            if room >= stack.size {
+                break;
+            }
+            if !self.can_insert(slot_index, stack) {
+                continue;
+            }
+
+            room = room.saturating_add(match self.get_slot(slot_index) {
+                Some(existing) if stack.can_merge_with(existing) => max_size.saturating_sub(existing.size),
+                Some(_) => 0,
+                None => max_size,
+            });
+        }
+
+        room.min(stack.size)
+    }
+
     /// Gets the number of slots in this container
     pub fn len(&self) -> usize {
         self.slots.len()
     }
+
+    /// Changes whenever a slot's contents, filter or lock are written
+    ///
+    /// Revisions only ever grow and are unique across containers, so comparing them with the last
+    /// seen one tells whether anything is to be redrawn.
+    pub fn slot_revision(&self, index: usize) -> u64 {
+        self.revisions.get(index).copied().unwrap_or(0)
+    }
+
+    /// The latest revision of any slot in this container
+    pub fn revision(&self) -> u64 {
+        self.revision
+    }
+
+    /// Starts journaling what slots held before they are written, returns the revision to hand to `finish_recording`
+    ///
+    /// Every call must be matched by exactly one `finish_recording`, the journal is kept until then.
+    pub fn start_recording(&mut self) -> u64 {
+        self.recorders += 1;
+        self.revision
+    }
+
+    /// The slots written since `since` along with what they held back then, in slot order
+    ///
+    /// Slots that were written back to their old contents are included too.
+    pub fn finish_recording(&mut self, since: u64) -> Vec<(usize, Option<ItemStack>)> {
+        let mut written = BTreeMap::new();
+        for record in self.journal.iter().filter(|record| record.revision > since) {
+            written.entry(record.index).or_insert_with(|| record.before.clone());
+        }
+
+        self.recorders = self.recorders.saturating_sub(1);
+        if self.recorders == 0 {
+            self.journal.clear();
+        }
+
+        written.into_iter().collect()
+    }
+
+    /// Bumps the slot's revision and journals its contents, call before changing them
+    fn before_write(&mut self, index: usize) {
+        let Some(slot) = self.slots.get(index) else { return; };
+        let before = if self.recorders > 0 { Some(slot.stack.clone()) } else { None };
+
+        self.touch(index);
+        if let Some(before) = before {
+            self.journal.push(SlotRecord { revision: self.revision, index, before });
+        }
+    }
+
+    fn touch(&mut self, index: usize) {
+        if let Some(slot_revision) = self.revisions.get_mut(index) {
+            self.revision = next_revision();
+            *slot_revision = self.revision;
+        }
+    }
 }
\ No newline at end of file
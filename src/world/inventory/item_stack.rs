@@ -1,10 +1,21 @@
 use crate::world::item::item::{Item, ItemProperties};
+use crate::world::item::metadata::ItemMetadata;
 
 /// ItemStack -> a stack of items with a size limit that can be stored in inventories
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct ItemStack {
     pub item: Option<Item>,
-    pub size: u32
+    pub size: u32,
+
+    /// Remaining durability of this particular stack, `None` for items without durability
+    pub durability: Option<u128>,
+
+    /// ID of the `ContainerType::Custom` container holding this stack's contents, for items with
+    /// `storage`. Assigned when the stack is first opened, see `ContainerManager::open_portable`
+    pub contents: Option<u32>,
+
+    /// Custom name, lore, enchantments, ... of this particular stack, boxed since most stacks have none
+    pub metadata: Option<Box<ItemMetadata>>,
 }
 
 /// ItemStack implementation
@@ -16,12 +27,18 @@ impl ItemStack {
             Self {
                 item: Some(item),
                 size,
+                durability: item.properties.durability,
+                contents: None,
+                metadata: None,
             }
         }
         else {
             Self {
                 item: Some(item),
                 size: 1,
+                durability: item.properties.durability,
+                contents: None,
+                metadata: None,
             }
         }
 
@@ -34,17 +51,49 @@ impl ItemStack {
     pub const fn empty() -> Self {
         Self {
             item: None,
-            size: 0
+            size: 0,
+            durability: None,
+            contents: None,
+            metadata: None,
+        }
+    }
+
+    /// Creates a copy of this stack with a different size, keeping per-stack data such as durability
+    pub fn with_size(&self, size: u32) -> ItemStack {
+        ItemStack {
+            size,
+            ..self.clone()
         }
     }
 
+    /// Attaches metadata to this stack, builder-style. Empty metadata is the same as none
+    pub fn with_metadata(mut self, metadata: ItemMetadata) -> Self {
+        self.metadata = (!metadata.is_empty()).then(|| Box::new(metadata));
+        self
+    }
+
+    /// The custom name of this stack if it has one, otherwise its item's display name
+    pub fn display_name(&self) -> &str {
+        self.metadata.as_ref()
+            .and_then(|metadata| metadata.custom_name.as_deref())
+            .or(self.item.map(|item| item.display_name))
+            .unwrap_or_default()
+    }
+
     /// Same name same max size? maybe make this more robust.
     ///
-    /// maybe check item IDs, types, enchantments, etc.
+    /// Stacks with differing wear never merge, otherwise a damaged tool could be "repaired" by stacking.
+    /// Stacks with differing metadata don't merge either, a named sword stays unique.
+    /// Stacks with contents never merge either, their contents would be lost.
     pub fn can_merge_with(&self, other: &ItemStack) -> bool {
         let self_properties: &ItemProperties = &self.item.unwrap().properties;
         let other_properties: &ItemProperties = &other.item.unwrap().properties;
-        self.item == other.item && self.item.unwrap().identifier == other.item.unwrap().identifier && self_properties.max_stack_size == other_properties.max_stack_size
+        self.item == other.item
+            && self.item.unwrap().identifier == other.item.unwrap().identifier
+            && self_properties.max_stack_size == other_properties.max_stack_size
+            && self.durability == other.durability
+            && self.contents.is_none() && other.contents.is_none()
+            && self.metadata == other.metadata
     }
 
     /// Try to dump held items onto another item stack
@@ -101,10 +150,45 @@ impl ItemStack {
         let half: u32 = self.size / 2;
         self.size -= half;
 
-        Some(ItemStack {
-            item: self.item.clone(), // same name bc it's literally the same thing
-            size: half
-        })
+        Some(self.with_size(half)) // same item and wear bc it's literally the same thing
+    }
+
+    /// The maximum durability of this stack's item, if it has any
+    pub fn max_durability(&self) -> Option<u128> {
+        self.item?.properties.durability
+    }
+
+    /// Remaining durability from 0.0 (broken) to 1.0 (new), `None` for items without durability
+    pub fn durability_fraction(&self) -> Option<f32> {
+        let (durability, max) = self.durability.zip(self.max_durability())?;
+        Some(if max == 0 { 0.0 } else { durability as f32 / max as f32 })
+    }
+
+    /// Wears this stack down by `amount`
+    ///
+    /// Returns `true` if the stack broke (durability reached zero). The caller is responsible
+    /// for clearing the slot, see `SlotContainer::damage_slot`.
+    pub fn damage(&mut self, amount: u128) -> bool {
+        let Some(durability) = &mut self.durability else {
+            return false; // items without durability can't break
+        };
+
+        *durability = durability.saturating_sub(amount);
+        *durability == 0
+    }
+
+    /// Restores up to `amount` durability, never exceeding the item's maximum
+    pub fn repair(&mut self, amount: u128) {
+        let max_durability = self.max_durability();
+
+        if let (Some(durability), Some(max)) = (&mut self.durability, max_durability) {
+            *durability = durability.saturating_add(amount).min(max);
+        }
+    }
+
+    /// Checks if this stack has been worn down completely
+    pub fn is_broken(&self) -> bool {
+        self.durability == Some(0)
     }
 
     /// Checks if this stack is empty (count = 0)
@@ -0,0 +1,291 @@
+use crate::world::inventory::containers::{ContainerLayout, ContainerPosition, ContainerType};
+use crate::world::inventory::filter::SlotFilter;
+use crate::world::inventory::inventory::SlotContainer;
+use crate::world::item::item::Item;
+use bevy::prelude::Resource;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+
+/// Save keys of the built-in container types, a custom kind can't use these as its identifier
+/// (nor anything containing `:`, which separates the identifier from the container ID)
+const RESERVED_IDENTIFIERS: &[&str] = &[
+    "player_inventory", "hotbar", "chest", "crafting_grid", "crafting_output", "furnace", "equipment",
+];
+
+/// Contents of a backpack item, which can't hold other backpacks
+pub const BACKPACK_STORAGE: ContainerKind = ContainerKind::new("backpack", "Backpack", 2, 9).portable(0);
+
+/// Kinds `ContainerKindRegistry::default` starts with, needed by the built-in items
+pub const BUILTIN_KINDS: &[ContainerKind] = &[BACKPACK_STORAGE];
+
+/// A container kind defined outside of this crate (backpacks, barrels, trade windows, ...)
+///
+/// Like `Item`, a kind is plain `'static` data and is usually declared as a constant. Containers of
+/// a kind are addressed by `ContainerType::Custom(kind, id)` and opened with
+/// `ContainerManager::open_custom`. Two kinds are the same kind if their identifiers match, the
+/// registry makes sure only one definition is in use for each identifier.
+#[derive(Debug, Copy, Clone)]
+pub struct ContainerKind {
+    pub identifier: &'static str, // also the prefix of the save key, e.g. "barrel:2"
+    pub title: &'static str,      // shown followed by the container ID (unless portable), empty for no header
+    pub slot_count: usize,
+    pub rows: usize,
+    pub columns: usize,
+    pub position: ContainerPosition,
+    pub sortable: bool,
+    pub slot_filters: &'static [(usize, SlotFilter)],
+    pub max_nesting: Option<usize>, // `Some` for kinds carried by items, see `portable`
+}
+
+impl ContainerKind {
+
+    /// A sortable `rows` x `columns` grid shown above the player inventory, like a chest
+    pub const fn new(identifier: &'static str, title: &'static str, rows: usize, columns: usize) -> Self {
+        Self {
+            identifier,
+            title,
+            slot_count: rows * columns,
+            rows,
+            columns,
+            position: ContainerPosition::Top,
+            sortable: true,
+            slot_filters: &[],
+            max_nesting: None,
+        }
+    }
+
+    /// Uses fewer slots than the grid has room for, the last row is left partially empty
+    pub const fn slot_count(mut self, slot_count: usize) -> Self {
+        self.slot_count = slot_count;
+        self
+    }
+
+    pub const fn position(mut self, position: ContainerPosition) -> Self {
+        self.position = position;
+        self
+    }
+
+    pub const fn sortable(mut self, sortable: bool) -> Self {
+        self.sortable = sortable;
+        self
+    }
+
+    /// Filters every container of this kind starts with, as `(slot index, filter)` pairs
+    pub const fn slot_filters(mut self, slot_filters: &'static [(usize, SlotFilter)]) -> Self {
+        self.slot_filters = slot_filters;
+        self
+    }
+
+    /// Makes this the kind of container carried by items whose `ItemProperties::storage` is its identifier
+    ///
+    /// Containers of a portable kind only accept items carrying portable containers whose own
+    /// `max_nesting` is lower, so they can be nested `max_nesting` levels deep at most and never
+    /// contain themselves.
+    pub const fn portable(mut self, max_nesting: usize) -> Self {
+        self.max_nesting = Some(max_nesting);
+        self
+    }
+
+    pub fn is_portable(&self) -> bool {
+        self.max_nesting.is_some()
+    }
+
+    /// Whether every field matches, not just the identifier
+    ///
+    /// `SlotFilter::Custom` predicates can't be compared and only count as the same variant.
+    pub fn same_definition(&self, other: &ContainerKind) -> bool {
+        let filters_match = self.slot_filters.len() == other.slot_filters.len()
+            && self.slot_filters.iter().zip(other.slot_filters).all(|((index, filter), (other_index, other_filter))| {
+                index == other_index && std::mem::discriminant(filter) == std::mem::discriminant(other_filter)
+            });
+
+        self.identifier == other.identifier
+            && self.title == other.title
+            && self.slot_count == other.slot_count
+            && self.rows == other.rows
+            && self.columns == other.columns
+            && self.position == other.position
+            && self.sortable == other.sortable
+            && self.max_nesting == other.max_nesting
+            && filters_match
+    }
+
+    /// Creates an empty container of this kind, with its slot filters applied
+    ///
+    /// A portable container made this way holds no other portable containers, give it
+    /// `ContainerKindRegistry::nestable_storage` for that.
+    pub fn create_container(&self) -> SlotContainer {
+        ContainerType::Custom(*self, 0).create_container(self.slot_count)
+    }
+
+    pub fn layout(&self, id: u32) -> ContainerLayout {
+        ContainerLayout {
+            container_type: ContainerType::Custom(*self, id),
+            slot_count: self.slot_count,
+            rows: self.rows,
+            columns: self.columns,
+            title: if self.title.is_empty() || self.is_portable() { self.title.to_string() } else { format!("{} {}", self.title, id) },
+            position: self.position,
+            linked: None,
+        }
+    }
+}
+
+impl PartialEq for ContainerKind {
+    fn eq(&self, other: &Self) -> bool {
+        self.identifier == other.identifier
+    }
+}
+
+impl Eq for ContainerKind {}
+
+impl Hash for ContainerKind {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.identifier.hash(state);
+    }
+}
+
+impl PartialOrd for ContainerKind {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for ContainerKind {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.identifier.cmp(other.identifier)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum ContainerKindError {
+    DuplicateIdentifier { identifier: String },
+    InvalidIdentifier { identifier: String },
+    TooManySlots { identifier: String, slot_count: usize, rows: usize, columns: usize },
+    Unregistered { identifier: String },
+    ConflictingDefinition { identifier: String }, // registered under the same identifier, but defined differently
+}
+
+impl fmt::Display for ContainerKindError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ContainerKindError::DuplicateIdentifier { identifier } => {
+                write!(f, "a container kind with identifier \"{}\" is already registered", identifier)
+            }
+            ContainerKindError::InvalidIdentifier { identifier } => {
+                write!(f, "\"{}\" is reserved for a built-in container type or contains ':'", identifier)
+            }
+            ContainerKindError::TooManySlots { identifier, slot_count, rows, columns } => {
+                write!(f, "container kind \"{}\" has {} slots but its {}x{} grid only fits {}", identifier, slot_count, rows, columns, rows * columns)
+            }
+            ContainerKindError::Unregistered { identifier } => {
+                write!(f, "container kind \"{}\" is not registered", identifier)
+            }
+            ContainerKindError::ConflictingDefinition { identifier } => {
+                write!(f, "container kind \"{}\" differs from the kind registered under that identifier", identifier)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ContainerKindError {}
+
+/// Every custom container kind known to the game, needed to load saves containing them
+#[derive(Resource)]
+pub struct ContainerKindRegistry {
+    kinds: HashMap<&'static str, ContainerKind>,
+}
+
+impl Default for ContainerKindRegistry {
+    fn default() -> Self {
+        Self::with_builtin_kinds()
+    }
+}
+
+impl ContainerKindRegistry {
+
+    /// Creates a registry without any kinds, not even the built-in ones
+    pub fn empty() -> Self {
+        Self { kinds: HashMap::new() }
+    }
+
+    /// Creates a registry holding every kind from `BUILTIN_KINDS`
+    pub fn with_builtin_kinds() -> Self {
+        let mut registry = Self::empty();
+
+        for kind in BUILTIN_KINDS {
+            registry.register(*kind).expect("invalid or duplicate identifier in BUILTIN_KINDS");
+        }
+
+        registry
+    }
+
+    /// Registers a kind, failing if its identifier is taken by another kind or can't be saved,
+    /// or if its slots don't fit its grid
+    pub fn register(&mut self, kind: ContainerKind) -> Result<(), ContainerKindError> {
+        if RESERVED_IDENTIFIERS.contains(&kind.identifier) || kind.identifier.contains(':') {
+            return Err(ContainerKindError::InvalidIdentifier {
+                identifier: kind.identifier.to_string(),
+            });
+        }
+
+        if kind.slot_count > kind.rows * kind.columns {
+            return Err(ContainerKindError::TooManySlots {
+                identifier: kind.identifier.to_string(),
+                slot_count: kind.slot_count,
+                rows: kind.rows,
+                columns: kind.columns,
+            });
+        }
+
+        if self.kinds.contains_key(kind.identifier) {
+            return Err(ContainerKindError::DuplicateIdentifier {
+                identifier: kind.identifier.to_string(),
+            });
+        }
+
+        self.kinds.insert(kind.identifier, kind);
+        Ok(())
+    }
+
+    /// Looks up a kind by its identifier, e.g. `"barrel"`
+    pub fn get(&self, identifier: &str) -> Option<ContainerKind> {
+        self.kinds.get(identifier).copied()
+    }
+
+    /// Checks that `kind` is registered, and is the very definition registered under its identifier
+    pub fn check(&self, kind: &ContainerKind) -> Result<(), ContainerKindError> {
+        let identifier = kind.identifier.to_string();
+
+        match self.kinds.get(kind.identifier) {
+            None => Err(ContainerKindError::Unregistered { identifier }),
+            Some(registered) if !registered.same_definition(kind) => Err(ContainerKindError::ConflictingDefinition { identifier }),
+            Some(_) => Ok(()),
+        }
+    }
+
+    /// The registered portable kind an item carries, see `ItemProperties::storage`
+    pub fn storage_kind(&self, item: &Item) -> Option<ContainerKind> {
+        item.properties.storage
+            .and_then(|identifier| self.get(identifier))
+            .filter(ContainerKind::is_portable)
+    }
+
+    /// Identifiers of the portable kinds a container of `kind` may hold, `None` if it isn't portable
+    ///
+    /// Those are the registered portable kinds with a lower `max_nesting`, see `SlotContainer::with_nestable_storage`.
+    pub fn nestable_storage(&self, kind: &ContainerKind) -> Option<Vec<&'static str>> {
+        let max_nesting = kind.max_nesting?;
+
+        Some(self.kinds.values()
+            .filter(|inner| inner.max_nesting.is_some_and(|inner_nesting| inner_nesting < max_nesting))
+            .map(|inner| inner.identifier)
+            .collect())
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &ContainerKind> {
+        self.kinds.values()
+    }
+}
@@ -3,4 +3,13 @@ pub mod systems;
 pub mod ui;
 pub mod inventory;
 pub mod item_stack;
-pub mod containers;
\ No newline at end of file
+pub mod containers;
+pub mod consumable;
+pub mod equipment;
+pub mod filter;
+pub mod furnace;
+pub mod history;
+pub mod kind;
+pub mod routing;
+pub mod save;
+pub mod sort;
\ No newline at end of file
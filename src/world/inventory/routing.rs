@@ -0,0 +1,222 @@
+use crate::world::inventory::containers::{ContainerManager, ContainerType, UIMode};
+use crate::world::inventory::equipment::{EQUIPMENT_FEET_SLOT, EQUIPMENT_HEAD_SLOT};
+use crate::world::inventory::furnace::{FURNACE_FUEL_SLOT, FURNACE_INPUT_SLOT};
+use bevy::prelude::Resource;
+use std::ops::Range;
+
+/// Which source containers a routing rule applies to
+#[derive(Debug, Clone)]
+pub enum ContainerMatch {
+    Any,
+    Exact(ContainerType),
+    Chest,         // any chest
+    CraftingGrid,  // any crafting grid
+    Furnace,       // any furnace
+    Kind(&'static str), // any container of the custom kind with this identifier
+    Matching(fn(&ContainerType) -> bool),
+}
+
+impl ContainerMatch {
+    pub fn matches(&self, container_type: &ContainerType) -> bool {
+        match self {
+            ContainerMatch::Any => true,
+            ContainerMatch::Exact(expected) => expected == container_type,
+            ContainerMatch::Chest => matches!(container_type, ContainerType::Chest(_)),
+            ContainerMatch::CraftingGrid => matches!(container_type, ContainerType::CraftingGrid(_)),
+            ContainerMatch::Furnace => matches!(container_type, ContainerType::Furnace(_)),
+            ContainerMatch::Kind(identifier) => matches!(container_type, ContainerType::Custom(kind, _) if kind.identifier == *identifier),
+            ContainerMatch::Matching(predicate) => predicate(container_type),
+        }
+    }
+}
+
+/// Which UI modes a routing rule applies to, ignoring the IDs of open containers
+#[derive(Debug, Clone)]
+pub enum UIModeMatch {
+    Any,
+    HotbarOnly,
+    Inventory,
+    Chest,
+    CraftingTable,
+    Furnace,
+    Custom, // any custom container kind
+    Kind(&'static str),
+    Matching(fn(&UIMode) -> bool),
+}
+
+impl UIModeMatch {
+    pub fn matches(&self, ui_mode: &UIMode) -> bool {
+        match self {
+            UIModeMatch::Any => true,
+            UIModeMatch::HotbarOnly => *ui_mode == UIMode::HotbarOnly,
+            UIModeMatch::Inventory => *ui_mode == UIMode::InventoryOpen,
+            UIModeMatch::Chest => matches!(ui_mode, UIMode::ChestOpen(_)),
+            UIModeMatch::CraftingTable => matches!(ui_mode, UIMode::CraftingTableOpen(_)),
+            UIModeMatch::Furnace => matches!(ui_mode, UIMode::FurnaceOpen(_)),
+            UIModeMatch::Custom => matches!(ui_mode, UIMode::CustomOpen(..)),
+            UIModeMatch::Kind(identifier) => matches!(ui_mode, UIMode::CustomOpen(kind, _) if kind.identifier == *identifier),
+            UIModeMatch::Matching(predicate) => predicate(ui_mode),
+        }
+    }
+}
+
+/// The container a routing target points at
+#[derive(Debug, Clone)]
+pub enum TargetContainer {
+    Fixed(ContainerType),
+    Open, // the chest (both halves of a double chest), furnace, crafting grid or custom container opened by the current UI mode
+}
+
+impl TargetContainer {
+    fn resolve(&self, container_manager: &ContainerManager) -> Vec<ContainerType> {
+        match self {
+            TargetContainer::Fixed(container_type) => vec![container_type.clone()],
+            TargetContainer::Open => container_manager.open_containers(),
+        }
+    }
+}
+
+/// One place shift-clicked items may go, optionally restricted to a range of slots
+#[derive(Debug, Clone)]
+pub struct RouteTarget {
+    pub container: TargetContainer,
+    pub slots: Option<Range<usize>>, // all slots when `None`
+}
+
+impl RouteTarget {
+    pub fn container(container_type: ContainerType) -> Self {
+        Self { container: TargetContainer::Fixed(container_type), slots: None }
+    }
+
+    pub fn open_container() -> Self {
+        Self { container: TargetContainer::Open, slots: None }
+    }
+
+    pub fn slots(mut self, slots: Range<usize>) -> Self {
+        self.slots = Some(slots);
+        self
+    }
+}
+
+/// Where items shift-clicked out of matching containers go, in order of preference
+#[derive(Debug, Clone)]
+pub struct RoutingRule {
+    pub source: ContainerMatch,
+    pub ui_mode: UIModeMatch,
+    pub targets: Vec<RouteTarget>,
+}
+
+impl RoutingRule {
+    pub fn new(source: ContainerMatch, ui_mode: UIModeMatch, targets: Vec<RouteTarget>) -> Self {
+        Self { source, ui_mode, targets }
+    }
+}
+
+/// A shift-click target with its slot range resolved for the current UI mode
+#[derive(Debug, Clone, PartialEq)]
+pub struct ResolvedTarget {
+    pub container_type: ContainerType,
+    pub slots: Option<Range<usize>>,
+}
+
+/// Data-driven shift-click routing, see `process_shift_click`
+///
+/// The last registered rule matching a source container and UI mode wins, so new container
+/// types (or mods of the defaults) only need to `register` their own rules.
+/// Slot filters still apply, targets an item isn't accepted by are skipped.
+#[derive(Resource, Debug, Clone)]
+pub struct ShiftClickRouting {
+    rules: Vec<RoutingRule>,
+}
+
+impl ShiftClickRouting {
+    pub fn empty() -> Self {
+        Self { rules: Vec::new() }
+    }
+
+    pub fn register(&mut self, rule: RoutingRule) {
+        self.rules.push(rule);
+    }
+
+    /// Where items shift-clicked out of `source` go in the current UI mode, empty if nowhere
+    pub fn targets(&self, source: &ContainerType, container_manager: &ContainerManager) -> Vec<ResolvedTarget> {
+        let ui_mode = &container_manager.ui_mode;
+        let Some(rule) = self.rules.iter().rev().find(|rule| rule.source.matches(source) && rule.ui_mode.matches(ui_mode)) else {
+            return Vec::new();
+        };
+
+        rule.targets.iter()
+            .flat_map(|target| {
+                target.container.resolve(container_manager).into_iter()
+                    .map(|container_type| ResolvedTarget { container_type, slots: target.slots.clone() })
+            })
+            .collect()
+    }
+}
+
+impl Default for ShiftClickRouting {
+    /// Minecraft-like defaults: between hotbar and inventory, into and out of the open container,
+    /// armour to its equipment slot from the inventory screen
+    fn default() -> Self {
+        let armour_slots = || RouteTarget::container(ContainerType::Equipment).slots(EQUIPMENT_HEAD_SLOT..EQUIPMENT_FEET_SLOT + 1);
+        let inventory_then_hotbar = || vec![
+            RouteTarget::container(ContainerType::PlayerInventory),
+            RouteTarget::container(ContainerType::Hotbar),
+        ];
+
+        let mut routing = Self::empty();
+
+        // out of crafting grids, equipment and furnaces
+        routing.register(RoutingRule::new(ContainerMatch::Any, UIModeMatch::Any, inventory_then_hotbar()));
+
+        // the inventory isn't visible, so there's nowhere to move the hotbar to
+        routing.register(RoutingRule::new(ContainerMatch::Any, UIModeMatch::HotbarOnly, Vec::new()));
+
+        // between hotbar and inventory
+        for mode in [UIModeMatch::Inventory, UIModeMatch::CraftingTable] {
+            routing.register(RoutingRule::new(ContainerMatch::Exact(ContainerType::Hotbar), mode.clone(), vec![
+                RouteTarget::container(ContainerType::PlayerInventory),
+            ]));
+            routing.register(RoutingRule::new(ContainerMatch::Exact(ContainerType::PlayerInventory), mode, vec![
+                RouteTarget::container(ContainerType::Hotbar),
+            ]));
+        }
+
+        // armour is equipped first from the inventory screen
+        routing.register(RoutingRule::new(ContainerMatch::Exact(ContainerType::Hotbar), UIModeMatch::Inventory, vec![
+            armour_slots(),
+            RouteTarget::container(ContainerType::PlayerInventory),
+        ]));
+        routing.register(RoutingRule::new(ContainerMatch::Exact(ContainerType::PlayerInventory), UIModeMatch::Inventory, vec![
+            armour_slots(),
+            RouteTarget::container(ContainerType::Hotbar),
+        ]));
+        routing.register(RoutingRule::new(ContainerMatch::CraftingGrid, UIModeMatch::Inventory, {
+            let mut targets = vec![armour_slots()];
+            targets.extend(inventory_then_hotbar());
+            targets
+        }));
+
+        // into and out of an open chest, custom containers behave like chests unless they register their own rules
+        for mode in [UIModeMatch::Chest, UIModeMatch::Custom] {
+            routing.register(RoutingRule::new(ContainerMatch::Exact(ContainerType::Hotbar), mode.clone(), vec![
+                RouteTarget::open_container(),
+                RouteTarget::container(ContainerType::PlayerInventory),
+            ]));
+            routing.register(RoutingRule::new(ContainerMatch::Exact(ContainerType::PlayerInventory), mode, vec![
+                RouteTarget::open_container(),
+            ]));
+        }
+        routing.register(RoutingRule::new(ContainerMatch::Chest, UIModeMatch::Chest, inventory_then_hotbar()));
+
+        // smeltables to the furnace input, fuel to its fuel slot
+        for source in [ContainerType::Hotbar, ContainerType::PlayerInventory] {
+            routing.register(RoutingRule::new(ContainerMatch::Exact(source), UIModeMatch::Furnace, vec![
+                RouteTarget::open_container().slots(FURNACE_INPUT_SLOT..FURNACE_INPUT_SLOT + 1),
+                RouteTarget::open_container().slots(FURNACE_FUEL_SLOT..FURNACE_FUEL_SLOT + 1),
+            ]));
+        }
+
+        routing
+    }
+}
@@ -0,0 +1,554 @@
+use crate::world::inventory::components::{HeldItem, SelectedHotbarSlot};
+use crate::world::inventory::containers::{ChestInfo, ContainerManager, ContainerType, CraftingStation, UIMode, CHEST_COLUMNS, MAX_CHEST_ROWS};
+use crate::world::inventory::equipment::EQUIPMENT_SLOT_COUNT;
+use crate::world::inventory::furnace::{FurnaceState, Furnaces};
+use crate::world::inventory::inventory::SlotContainer;
+use crate::world::inventory::kind::ContainerKindRegistry;
+use crate::world::inventory::item_stack::ItemStack;
+use crate::world::item::metadata::ItemMetadata;
+use crate::world::item::registry::ItemRegistry;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+use std::path::Path;
+
+/// The version written by `save_inventory`, bump this (and add a migration) whenever the format changes
+pub const SAVE_FORMAT_VERSION: u32 = 1;
+
+pub const DEFAULT_SAVE_PATH: &str = "saves/inventory.ron";
+
+/// Upgrades a raw save from one version to the next
+pub type SaveMigration = fn(ron::Value) -> Result<ron::Value, SaveError>;
+
+/// Migrations indexed by the version they upgrade *from*, e.g. `MIGRATIONS[0]` turns a version 0 save into version 1
+///
+/// Version 1 is the first on-disk format, so there is nothing to migrate yet.
+const MIGRATIONS: &[SaveMigration] = &[];
+
+#[derive(Debug)]
+pub enum SaveError {
+    Io(std::io::Error),
+    Serialize(ron::Error),
+    Parse(ron::error::SpannedError),
+    UnsupportedVersion { found: u32, supported: u32 }, // written by a newer version
+    MissingMigration { from: u32 }, // an older version without a way to upgrade it
+    UnknownContainer { key: String },
+    DuplicateContainer { key: String },
+    UnknownItem { identifier: String },
+    SlotOutOfRange { container: String, index: usize, slot_count: usize },
+    InvalidSlotCount { container: String, slot_count: usize }, // doesn't fit the container's type
+    RejectedBySlot { container: String, index: usize, identifier: String }, // the slot's filter doesn't take the item
+    InvalidStackSize { identifier: String, size: u32, max_stack_size: u32 },
+    InvalidDurability { identifier: String, durability: u128, max_durability: Option<u128> },
+    InvalidChestLink { chest_id: u32, linked_chest: u32 },
+}
+
+impl fmt::Display for SaveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SaveError::Io(error) => write!(f, "could not access save file: {}", error),
+            SaveError::Serialize(error) => write!(f, "could not serialize inventory: {}", error),
+            SaveError::Parse(error) => write!(f, "could not parse save file: {}", error),
+            SaveError::UnsupportedVersion { found, supported } => {
+                write!(f, "save format version {} is newer than the supported version {}", found, supported)
+            }
+            SaveError::MissingMigration { from } => {
+                write!(f, "no migration from save format version {} to version {}", from, from + 1)
+            }
+            SaveError::UnknownContainer { key } => write!(f, "unknown container \"{}\"", key),
+            SaveError::DuplicateContainer { key } => write!(f, "container \"{}\" is saved more than once", key),
+            SaveError::UnknownItem { identifier } => write!(f, "unknown item \"{}\"", identifier),
+            SaveError::SlotOutOfRange { container, index, slot_count } => {
+                write!(f, "slot {} is out of range for container \"{}\" with {} slots", index, container, slot_count)
+            }
+            SaveError::InvalidSlotCount { container, slot_count } => {
+                write!(f, "container \"{}\" can't have {} slots", container, slot_count)
+            }
+            SaveError::RejectedBySlot { container, index, identifier } => {
+                write!(f, "slot {} of container \"{}\" doesn't accept \"{}\"", index, container, identifier)
+            }
+            SaveError::InvalidStackSize { identifier, size, max_stack_size } => {
+                write!(f, "stack of {} \"{}\" exceeds the maximum stack size of {}", size, identifier, max_stack_size)
+            }
+            SaveError::InvalidDurability { identifier, durability, max_durability } => {
+                write!(f, "durability {} of \"{}\" is invalid (maximum {:?})", durability, identifier, max_durability)
+            }
+            SaveError::InvalidChestLink { chest_id, linked_chest } => {
+                write!(f, "chest {} can't be linked to chest {}", chest_id, linked_chest)
+            }
+        }
+    }
+}
+
+impl std::error::Error for SaveError {}
+
+impl From<std::io::Error> for SaveError {
+    fn from(error: std::io::Error) -> Self {
+        SaveError::Io(error)
+    }
+}
+
+impl From<ron::Error> for SaveError {
+    fn from(error: ron::Error) -> Self {
+        SaveError::Serialize(error)
+    }
+}
+
+impl From<ron::error::SpannedError> for SaveError {
+    fn from(error: ron::error::SpannedError) -> Self {
+        SaveError::Parse(error)
+    }
+}
+
+/// The on-disk representation of every container, the selected hotbar slot and the cursor-held item
+///
+/// Items are stored by their `identifier` and containers by a string key (see `container_key`),
+/// so the format doesn't depend on the layout of any Rust type.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InventorySave {
+    pub version: u32,
+    pub selected_hotbar_slot: usize,
+    pub held_item: Option<SavedStack>,
+    pub available_chests: Vec<u32>,
+    pub containers: Vec<SavedContainer>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub chests: Vec<SavedChest>, // only chests with a title or a linked half are written
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub furnaces: Vec<SavedFurnace>, // only furnaces that are burning or smelting are written
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SavedFurnace {
+    pub furnace_id: u32,
+    pub burn_time_left: f32,
+    pub burn_time_total: f32,
+    pub cook_time: f32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SavedChest {
+    pub chest_id: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub linked_chest: Option<u32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SavedContainer {
+    pub container: String,
+    pub slot_count: usize,
+    pub slots: Vec<SavedSlot>, // only occupied slots are written
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub locked_slots: Vec<usize>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SavedSlot {
+    pub index: usize,
+    pub stack: SavedStack,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SavedStack {
+    pub item: String,
+    pub size: u32,
+    #[serde(default)]
+    pub durability: Option<u64>, // ron can't round-trip u128 through migrations, and no item comes close to u64::MAX
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub contents: Option<u32>, // ID of the custom container holding a backpack's items
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<ItemMetadata>,
+}
+
+/// Just enough of a save to figure out which migrations it needs
+#[derive(Deserialize)]
+struct SaveHeader {
+    version: u32,
+}
+
+impl InventorySave {
+
+    /// Captures the current state of every container and furnace
+    pub fn capture(
+        container_manager: &ContainerManager,
+        furnaces: &Furnaces,
+        selected_hotbar_slot: &SelectedHotbarSlot,
+        held_item: &HeldItem,
+    ) -> Self {
+        let mut containers: Vec<SavedContainer> = container_manager.containers.iter()
+            .map(|(container_type, container)| SavedContainer {
+                container: container_key(container_type),
+                slot_count: container.len(),
+                slots: (0..container.len())
+                    .filter_map(|index| {
+                        container.get_slot(index).map(|stack| SavedSlot {
+                            index,
+                            stack: SavedStack::from_stack(stack),
+                        })
+                    })
+                    .collect(),
+                locked_slots: (0..container.len()).filter(|&index| container.is_slot_locked(index)).collect(),
+            })
+            .collect();
+
+        // HashMap order is random, keep the file stable between saves
+        containers.sort_by(|a, b| a.container.cmp(&b.container));
+
+        let mut chests: Vec<SavedChest> = container_manager.chest_info.iter()
+            .filter(|(_, info)| **info != ChestInfo::default())
+            .map(|(&chest_id, info)| SavedChest {
+                chest_id,
+                title: info.title.clone(),
+                linked_chest: info.linked_chest,
+            })
+            .collect();
+        chests.sort_by_key(|chest| chest.chest_id);
+
+        let mut saved_furnaces: Vec<SavedFurnace> = furnaces.states.iter()
+            .filter(|(_, state)| !state.is_idle())
+            .map(|(&furnace_id, state)| SavedFurnace {
+                furnace_id,
+                burn_time_left: state.burn_time_left,
+                burn_time_total: state.burn_time_total,
+                cook_time: state.cook_time,
+            })
+            .collect();
+        saved_furnaces.sort_by_key(|furnace| furnace.furnace_id);
+
+        Self {
+            version: SAVE_FORMAT_VERSION,
+            selected_hotbar_slot: selected_hotbar_slot.slot_index,
+            held_item: held_item.stack.as_ref().map(SavedStack::from_stack),
+            available_chests: container_manager.available_chests.clone(),
+            containers,
+            chests,
+            furnaces: saved_furnaces,
+        }
+    }
+
+    /// Validates the save against the item and container kind registries and replaces the current inventory state with it
+    ///
+    /// Nothing is modified unless the whole save is valid.
+    pub fn apply(
+        &self,
+        item_registry: &ItemRegistry,
+        container_kinds: &ContainerKindRegistry,
+        container_manager: &mut ContainerManager,
+        furnaces: &mut Furnaces,
+        selected_hotbar_slot: &mut SelectedHotbarSlot,
+        held_item: &mut HeldItem,
+    ) -> Result<(), SaveError> {
+        let mut containers = HashMap::new();
+
+        for saved_container in &self.containers {
+            let container_type = parse_container_key(&saved_container.container, container_kinds)?;
+            let valid_slot_count = match container_type.fixed_slot_count() {
+                Some(slot_count) => saved_container.slot_count == slot_count,
+                None => saved_container.slot_count % CHEST_COLUMNS == 0
+                    && (1..=MAX_CHEST_ROWS).contains(&(saved_container.slot_count / CHEST_COLUMNS)),
+            };
+            if !valid_slot_count {
+                return Err(SaveError::InvalidSlotCount {
+                    container: saved_container.container.clone(),
+                    slot_count: saved_container.slot_count,
+                });
+            }
+
+            let mut container = container_type.create_container(saved_container.slot_count);
+            if let ContainerType::Custom(kind, _) = &container_type {
+                container = container.with_nestable_storage(container_kinds.nestable_storage(kind));
+            }
+
+            // keep filters set up at runtime for containers that still exist
+            if let Some(existing) = container_manager.get_container(&container_type) {
+                container.copy_slot_filters_from(existing);
+            }
+
+            for saved_slot in &saved_container.slots {
+                let stack = saved_slot.stack.to_stack(item_registry)?;
+                if saved_slot.index >= container.len() {
+                    return Err(SaveError::SlotOutOfRange {
+                        container: saved_container.container.clone(),
+                        index: saved_slot.index,
+                        slot_count: saved_container.slot_count,
+                    });
+                }
+
+                // output slots hold whatever was crafted or smelted into them, everything else has to pass the filter
+                if !container.is_output_slot(saved_slot.index) && !container.can_insert(saved_slot.index, &stack) {
+                    return Err(SaveError::RejectedBySlot {
+                        container: saved_container.container.clone(),
+                        index: saved_slot.index,
+                        identifier: saved_slot.stack.item.clone(),
+                    });
+                }
+                let _ = container.set_slot(saved_slot.index, Some(stack));
+            }
+
+            for &index in &saved_container.locked_slots {
+                container.set_slot_locked(index, true).map_err(|_| SaveError::SlotOutOfRange {
+                    container: saved_container.container.clone(),
+                    index,
+                    slot_count: saved_container.slot_count,
+                })?;
+            }
+
+            if containers.insert(container_type, container).is_some() {
+                return Err(SaveError::DuplicateContainer { key: saved_container.container.clone() });
+            }
+        }
+
+        // older saves (or hand-edited ones) may be missing the player's own containers
+        containers.entry(ContainerType::Hotbar).or_insert_with(|| SlotContainer::new(9));
+        containers.entry(ContainerType::PlayerInventory).or_insert_with(|| SlotContainer::new(27));
+        containers.entry(ContainerType::Equipment)
+            .or_insert_with(|| ContainerType::Equipment.create_container(EQUIPMENT_SLOT_COUNT));
+        containers.entry(ContainerType::CraftingGrid(CraftingStation::Player)).or_insert_with(|| SlotContainer::new(4));
+        containers.entry(ContainerType::CraftingOutput(CraftingStation::Player))
+            .or_insert_with(|| ContainerType::CraftingOutput(CraftingStation::Player).create_container(1));
+
+        let hotbar_size = containers[&ContainerType::Hotbar].len();
+        if self.selected_hotbar_slot >= hotbar_size {
+            return Err(SaveError::SlotOutOfRange {
+                container: container_key(&ContainerType::Hotbar),
+                index: self.selected_hotbar_slot,
+                slot_count: hotbar_size,
+            });
+        }
+
+        let held_stack = self.held_item.as_ref()
+            .map(|stack| stack.to_stack(item_registry))
+            .transpose()?;
+
+        // both halves of a double chest have to exist and point at each other
+        let mut chest_info: HashMap<u32, ChestInfo> = HashMap::new();
+        for saved_chest in &self.chests {
+            for chest_id in std::iter::once(saved_chest.chest_id).chain(saved_chest.linked_chest) {
+                if !containers.contains_key(&ContainerType::Chest(chest_id)) {
+                    return Err(SaveError::UnknownContainer { key: container_key(&ContainerType::Chest(chest_id)) });
+                }
+            }
+
+            chest_info.entry(saved_chest.chest_id).or_default().title = saved_chest.title.clone();
+        }
+        for saved_chest in &self.chests {
+            let Some(linked_id) = saved_chest.linked_chest else { continue; };
+            let is_linked_elsewhere = |chest_id: u32, other_id: u32| chest_info.get(&chest_id)
+                .and_then(|info| info.linked_chest)
+                .is_some_and(|linked| linked != other_id);
+
+            if linked_id == saved_chest.chest_id
+                || is_linked_elsewhere(saved_chest.chest_id, linked_id)
+                || is_linked_elsewhere(linked_id, saved_chest.chest_id) {
+                return Err(SaveError::InvalidChestLink { chest_id: saved_chest.chest_id, linked_chest: linked_id });
+            }
+
+            chest_info.entry(saved_chest.chest_id).or_default().linked_chest = Some(linked_id);
+            chest_info.entry(linked_id).or_default().linked_chest = Some(saved_chest.chest_id);
+        }
+
+        // burn and cook progress only makes sense for furnaces that exist
+        let mut furnace_states = HashMap::new();
+        for saved_furnace in &self.furnaces {
+            let furnace_type = ContainerType::Furnace(saved_furnace.furnace_id);
+            if !containers.contains_key(&furnace_type) {
+                return Err(SaveError::UnknownContainer { key: container_key(&furnace_type) });
+            }
+
+            let state = FurnaceState {
+                burn_time_left: saved_furnace.burn_time_left.max(0.0),
+                burn_time_total: saved_furnace.burn_time_total.max(0.0),
+                cook_time: saved_furnace.cook_time.max(0.0),
+            };
+            if furnace_states.insert(saved_furnace.furnace_id, state).is_some() {
+                return Err(SaveError::DuplicateContainer { key: container_key(&furnace_type) });
+            }
+        }
+
+        // everything checks out, swap the new state in
+        let mut available_chests = self.available_chests.clone();
+        for container_type in containers.keys() {
+            if let ContainerType::Chest(chest_id) = container_type {
+                if !available_chests.contains(chest_id) {
+                    available_chests.push(*chest_id);
+                }
+            }
+        }
+
+        container_manager.containers = containers;
+        container_manager.available_chests = available_chests;
+        container_manager.chest_info = chest_info;
+        furnaces.states = furnace_states;
+
+        // the open chest, crafting table or furnace may not exist in the loaded state
+        match container_manager.ui_mode {
+            UIMode::ChestOpen(_) => container_manager.close_chest(),
+            UIMode::CraftingTableOpen(_) => container_manager.close_crafting_table(),
+            UIMode::FurnaceOpen(_) => container_manager.close_furnace(),
+            UIMode::CustomOpen(..) => container_manager.close_custom(),
+            _ => {}
+        }
+
+        selected_hotbar_slot.slot_index = self.selected_hotbar_slot;
+        held_item.stack = held_stack;
+
+        Ok(())
+    }
+
+    /// Parses a save, upgrading it to the current format version if needed
+    pub fn from_ron(contents: &str) -> Result<Self, SaveError> {
+        let header: SaveHeader = ron::from_str(contents)?;
+
+        if header.version > SAVE_FORMAT_VERSION {
+            return Err(SaveError::UnsupportedVersion {
+                found: header.version,
+                supported: SAVE_FORMAT_VERSION,
+            });
+        }
+
+        if header.version == SAVE_FORMAT_VERSION {
+            return Ok(ron::from_str(contents)?);
+        }
+
+        let mut value: ron::Value = ron::from_str(contents)?;
+        for version in header.version..SAVE_FORMAT_VERSION {
+            let migration = MIGRATIONS.get(version as usize).ok_or(SaveError::MissingMigration { from: version })?;
+            value = migration(value)?;
+        }
+
+        let mut save: InventorySave = value.into_rust().map_err(SaveError::Serialize)?;
+        save.version = SAVE_FORMAT_VERSION;
+        Ok(save)
+    }
+
+    pub fn to_ron(&self) -> Result<String, SaveError> {
+        Ok(ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())?)
+    }
+}
+
+impl SavedStack {
+    fn from_stack(stack: &ItemStack) -> Self {
+        Self {
+            item: stack.item.map(|item| item.identifier.to_string()).unwrap_or_default(),
+            size: stack.size,
+            durability: stack.durability.map(|durability| durability.min(u64::MAX as u128) as u64),
+            contents: stack.contents,
+            metadata: stack.metadata.as_deref().cloned(),
+        }
+    }
+
+    fn to_stack(&self, item_registry: &ItemRegistry) -> Result<ItemStack, SaveError> {
+        let item = item_registry.get(&self.item).ok_or_else(|| SaveError::UnknownItem {
+            identifier: self.item.clone(),
+        })?;
+
+        if self.size == 0 || self.size > item.properties.max_stack_size {
+            return Err(SaveError::InvalidStackSize {
+                identifier: self.item.clone(),
+                size: self.size,
+                max_stack_size: item.properties.max_stack_size,
+            });
+        }
+
+        let mut stack = ItemStack::new(item, self.size);
+        stack.contents = self.contents.filter(|_| item.properties.storage.is_some());
+        if let Some(metadata) = &self.metadata {
+            stack = stack.with_metadata(metadata.clone());
+        }
+
+        match (self.durability.map(u128::from), item.properties.durability) {
+            (Some(durability), Some(max)) if durability <= max => stack.durability = Some(durability),
+            (None, _) => {} // missing durability means a fresh item
+            (Some(durability), max_durability) => {
+                return Err(SaveError::InvalidDurability {
+                    identifier: self.item.clone(),
+                    durability,
+                    max_durability,
+                });
+            }
+        }
+
+        Ok(stack)
+    }
+}
+
+/// Writes the current inventory state to `path`, creating parent directories as needed
+pub fn save_inventory(
+    path: impl AsRef<Path>,
+    container_manager: &ContainerManager,
+    furnaces: &Furnaces,
+    selected_hotbar_slot: &SelectedHotbarSlot,
+    held_item: &HeldItem,
+) -> Result<(), SaveError> {
+    let path = path.as_ref();
+    let contents = InventorySave::capture(container_manager, furnaces, selected_hotbar_slot, held_item).to_ron()?;
+
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(path, contents)?;
+    Ok(())
+}
+
+/// Reads and validates the save at `path`, then replaces the current inventory state with it
+pub fn load_inventory(
+    path: impl AsRef<Path>,
+    item_registry: &ItemRegistry,
+    container_kinds: &ContainerKindRegistry,
+    container_manager: &mut ContainerManager,
+    furnaces: &mut Furnaces,
+    selected_hotbar_slot: &mut SelectedHotbarSlot,
+    held_item: &mut HeldItem,
+) -> Result<(), SaveError> {
+    let contents = std::fs::read_to_string(path)?;
+    InventorySave::from_ron(&contents)?.apply(item_registry, container_kinds, container_manager, furnaces, selected_hotbar_slot, held_item)
+}
+
+/// Stable string key for a container, e.g. `"hotbar"` or `"chest:2"`
+fn container_key(container_type: &ContainerType) -> String {
+    match container_type {
+        ContainerType::PlayerInventory => "player_inventory".to_string(),
+        ContainerType::Hotbar => "hotbar".to_string(),
+        ContainerType::Chest(chest_id) => format!("chest:{}", chest_id),
+        ContainerType::CraftingGrid(station) => format!("crafting_grid:{}", station_key(station)),
+        ContainerType::CraftingOutput(station) => format!("crafting_output:{}", station_key(station)),
+        ContainerType::Furnace(furnace_id) => format!("furnace:{}", furnace_id),
+        ContainerType::Equipment => "equipment".to_string(),
+        ContainerType::Custom(kind, id) => format!("{}:{}", kind.identifier, id),
+    }
+}
+
+fn parse_container_key(key: &str, container_kinds: &ContainerKindRegistry) -> Result<ContainerType, SaveError> {
+    let unknown = || SaveError::UnknownContainer { key: key.to_string() };
+
+    match key.split_once(':') {
+        None if key == "player_inventory" => Ok(ContainerType::PlayerInventory),
+        None if key == "hotbar" => Ok(ContainerType::Hotbar),
+        None if key == "equipment" => Ok(ContainerType::Equipment),
+        Some(("chest", chest_id)) => chest_id.parse().map(ContainerType::Chest).map_err(|_| unknown()),
+        Some(("crafting_grid", station)) => parse_station_key(station).map(ContainerType::CraftingGrid).ok_or_else(unknown),
+        Some(("furnace", furnace_id)) => furnace_id.parse().map(ContainerType::Furnace).map_err(|_| unknown()),
+        Some(("crafting_output", station)) => parse_station_key(station).map(ContainerType::CraftingOutput).ok_or_else(unknown),
+        Some((identifier, id)) => {
+            let kind = container_kinds.get(identifier).ok_or_else(unknown)?;
+            id.parse().map(|id| ContainerType::Custom(kind, id)).map_err(|_| unknown())
+        }
+        _ => Err(unknown()),
+    }
+}
+
+fn station_key(station: &CraftingStation) -> String {
+    match station {
+        CraftingStation::Player => "player".to_string(),
+        CraftingStation::Table(table_id) => format!("table:{}", table_id),
+    }
+}
+
+fn parse_station_key(key: &str) -> Option<CraftingStation> {
+    match key.split_once(':') {
+        None if key == "player" => Some(CraftingStation::Player),
+        Some(("table", table_id)) => table_id.parse().map(CraftingStation::Table).ok(),
+        _ => None,
+    }
+}
@@ -0,0 +1,154 @@
+use crate::world::inventory::filter::SlotFilter;
+use crate::world::inventory::inventory::SlotContainer;
+use crate::world::inventory::item_stack::ItemStack;
+use bevy::prelude::Resource;
+use std::cmp::Ordering;
+
+/// How `sort_container` orders stacks, ties are broken by identifier and then by size
+#[derive(Debug, Copy, Clone)]
+pub enum SortOrder {
+    Identifier,
+    DisplayName,
+    StackSize, // largest stacks first
+    Custom(fn(&ItemStack, &ItemStack) -> Ordering),
+}
+
+impl SortOrder {
+    pub fn compare(&self, a: &ItemStack, b: &ItemStack) -> Ordering {
+        let identifier = |stack: &ItemStack| stack.item.map(|item| item.identifier);
+
+        let ordering = match self {
+            SortOrder::Identifier => identifier(a).cmp(&identifier(b)),
+            SortOrder::DisplayName => a.display_name().cmp(b.display_name()), // custom names included
+            SortOrder::StackSize => b.size.cmp(&a.size),
+            SortOrder::Custom(compare) => compare(a, b),
+        };
+
+        ordering
+            .then_with(|| identifier(a).cmp(&identifier(b)))
+            .then_with(|| b.size.cmp(&a.size))
+    }
+}
+
+/// Sorting preferences used by the sort button and middle-click shortcut
+#[derive(Resource, Debug, Clone)]
+pub struct SortSettings {
+    pub order: SortOrder,
+    pub include_hotbar: bool, // the hotbar is laid out by hand, so it isn't sorted by default
+}
+
+impl Default for SortSettings {
+    fn default() -> Self {
+        Self {
+            order: SortOrder::Identifier,
+            include_hotbar: false,
+        }
+    }
+}
+
+/// Merges partial stacks of the same item and sorts a container's contents
+///
+/// Locked and output-only slots are left alone and every stack only ends up in a slot whose filter
+/// accepts it. Slots with a filter only take the stacks that don't fit anywhere else, plus the last
+/// ones in sort order if the unfiltered slots run out. If the stacks can't all be placed that way
+/// the container is left unchanged and `false` is returned.
+pub fn sort_container(container: &mut SlotContainer, order: &SortOrder) -> bool {
+    let sortable: Vec<usize> = (0..container.len())
+        .filter(|&index| !container.is_slot_locked(index) && !container.is_output_slot(index))
+        .collect();
+
+    let mut stacks = consolidate_stacks(sortable.iter().filter_map(|&index| container.get_slot(index).cloned()).collect());
+    stacks.sort_by(|a, b| order.compare(a, b));
+
+    let (restricted, unrestricted): (Vec<usize>, Vec<usize>) = sortable.iter().copied()
+        .partition(|&index| !matches!(container.slot_filter(index), SlotFilter::Any));
+
+    // unfiltered slots all accept the same stacks
+    let fits_unrestricted: Vec<bool> = stacks.iter()
+        .map(|stack| unrestricted.first().is_some_and(|&index| container.can_insert(index, stack)))
+        .collect();
+    let fits_restricted: Vec<Vec<bool>> = stacks.iter()
+        .map(|stack| restricted.iter().map(|&index| container.can_insert(index, stack)).collect())
+        .collect();
+
+    // stacks that only fit filtered slots have to get one
+    let mut assigned: Vec<Option<usize>> = vec![None; restricted.len()];
+    for stack_index in (0..stacks.len()).filter(|&stack_index| !fits_unrestricted[stack_index]) {
+        if !assign_restricted_slot(stack_index, &fits_restricted, &mut assigned, &mut vec![false; restricted.len()]) {
+            return false;
+        }
+    }
+
+    // the rest fill the unfiltered slots in order, the last ones move to filtered slots if those run out
+    let mut overflow = fits_unrestricted.iter().filter(|&&fits| fits).count().saturating_sub(unrestricted.len());
+    for stack_index in (0..stacks.len()).rev().filter(|&stack_index| fits_unrestricted[stack_index]) {
+        if overflow == 0 {
+            break;
+        }
+        if assign_restricted_slot(stack_index, &fits_restricted, &mut assigned, &mut vec![false; restricted.len()]) {
+            overflow -= 1;
+        }
+    }
+
+    if overflow > 0 {
+        return false;
+    }
+
+    let mut stacks: Vec<Option<ItemStack>> = stacks.into_iter().map(Some).collect();
+    let mut placed: Vec<(usize, ItemStack)> = restricted.iter().zip(&assigned)
+        .filter_map(|(&index, stack_index)| stack_index.and_then(|stack_index| stacks[stack_index].take()).map(|stack| (index, stack)))
+        .collect();
+    placed.extend(unrestricted.iter().copied().zip(stacks.into_iter().flatten()));
+
+    for &index in &sortable {
+        container.set_slot_unchecked(index, None);
+    }
+    for (index, stack) in placed {
+        container.set_slot_unchecked(index, Some(stack));
+    }
+
+    true
+}
+
+/// Finds a filtered slot for a stack, moving stacks placed earlier to other slots they fit if needed
+///
+/// Stacks that already have a slot keep one, so placing them in any order finds room for as many as possible.
+fn assign_restricted_slot(stack_index: usize, fits: &[Vec<bool>], assigned: &mut [Option<usize>], visited: &mut [bool]) -> bool {
+    for slot in 0..assigned.len() {
+        if !fits[stack_index][slot] || visited[slot] {
+            continue;
+        }
+        visited[slot] = true;
+
+        let free = match assigned[slot] {
+            Some(other) => assign_restricted_slot(other, fits, assigned, visited),
+            None => true,
+        };
+        if free {
+            assigned[slot] = Some(stack_index);
+            return true;
+        }
+    }
+
+    false
+}
+
+/// Merges stacks of the same item, filling each up to its max stack size
+fn consolidate_stacks(stacks: Vec<ItemStack>) -> Vec<ItemStack> {
+    let mut merged: Vec<ItemStack> = Vec::new();
+
+    for mut stack in stacks {
+        for existing in merged.iter_mut() {
+            if stack.size == 0 {
+                break;
+            }
+            stack.try_merge(existing);
+        }
+
+        if stack.size > 0 {
+            merged.push(stack);
+        }
+    }
+
+    merged
+}
@@ -1,9 +1,16 @@
 use crate::world::inventory::item_stack::ItemStack;
 
+/// The stack's name, then its enchantments and lore, then its size if there's more than one
 pub fn format_item_display(stack: &ItemStack) -> String {
+    let mut lines = vec![stack.display_name().to_string()];
+
+    if let Some(metadata) = &stack.metadata {
+        lines.extend(metadata.description_lines());
+    }
+
     if stack.size > 1 {
-        format!("{}\n{}", stack.item.unwrap().display_name, stack.size)
-    } else {
-        stack.item.unwrap().display_name.to_string()
+        lines.push(stack.size.to_string());
     }
+
+    lines.join("\n")
 }
\ No newline at end of file
@@ -2,6 +2,7 @@ use super::components::*;
 use crate::world::inventory::containers::{ContainerLayout, ContainerManager, ContainerPosition, ContainerType, ContainerUI};
 use crate::world::inventory::item_stack::ItemStack;
 use crate::world::item::*;
+use crate::world::item::definitions::{SPRITESHEET_COLUMNS, SPRITESHEET_ROWS};
 use bevy::prelude::*;
 
 /// Resource to hold the item spritesheet texture atlas
@@ -23,8 +24,8 @@ pub fn setup_game(
     // The spritesheet is 128x144 pixels (8 columns by 9 rows), each sprite 16x16 pixels, tightly packed
     let layout = TextureAtlasLayout::from_grid(
         Vec2::new(16.0, 16.0), // sprite size
-        8, // columns
-        9, // rows (9 rows total)
+        SPRITESHEET_COLUMNS as usize, // columns
+        SPRITESHEET_ROWS as usize, // rows (9 rows total)
         Some(Vec2::ZERO), // no padding between sprites
         Some(Vec2::ZERO), // no offset from edge
     );
@@ -42,13 +43,17 @@ pub fn setup_game(
         let _ = hotbar.set_slot(0, Some(ItemStack::new(items::APPLE, 16)));
         let _ = hotbar.set_slot(1, Some(ItemStack::new(items::BOW, 1)));
         let _ = hotbar.set_slot(2, Some(ItemStack::new(items::IRON_SWORD, 1)));
+        let _ = hotbar.set_slot(3, Some(ItemStack::new(items::HEALTH_POTION, 1)));
     }
 
     // Add items to player inventory
     if let Some(inventory) = container_manager.containers.get_mut(&ContainerType::PlayerInventory) {
         let _ = inventory.set_slot(0, Some(ItemStack::new(items::APPLE, 32)));
         let _ = inventory.set_slot(1, Some(ItemStack::new(items::RING, 8)));
+        let _ = inventory.set_slot(2, Some(ItemStack::new(items::BACKPACK, 1)));
         let _ = inventory.set_slot(9, Some(ItemStack::new(items::GLASS_BOTTLE, 12)));
+        let _ = inventory.set_slot(10, Some(ItemStack::new(items::STICK, 24)));
+        let _ = inventory.set_slot(11, Some(ItemStack::new(items::PAPER, 8)));
     }
 
     // Add different items to different chests
@@ -56,6 +61,12 @@ pub fn setup_game(
         let _ = chest1.set_slot(0, Some(ItemStack::new(items::BOW, 1)));
         let _ = chest1.set_slot(1, Some(ItemStack::new(items::IRON_SWORD, 1)));
         let _ = chest1.set_slot(2, Some(ItemStack::new(items::CHEESE, 3)));
+        let _ = chest1.set_slot(3, Some(ItemStack::new(items::IRON_SWORD, 1).with_metadata(
+            metadata::ItemMetadata::new()
+                .custom_name("Dawnbreaker")
+                .enchantment("sharpness", 2)
+                .lore_line("Found in an old chest"),
+        )));
     }
 
     if let Some(chest2) = container_manager.containers.get_mut(&ContainerType::Chest(2)) {
@@ -67,22 +78,36 @@ pub fn setup_game(
     if let Some(chest3) = container_manager.containers.get_mut(&ContainerType::Chest(3)) {
         let _ = chest3.set_slot(0, Some(ItemStack::new(items::IRON_SWORD, 1)));
         let _ = chest3.set_slot(1, Some(ItemStack::new(items::RING, 10)));
+        let _ = chest3.set_slot(2, Some(ItemStack::new(items::IRON_HELMET, 1)));
+        let _ = chest3.set_slot(3, Some(ItemStack::new(items::IRON_CHESTPLATE, 1)));
+        let _ = chest3.set_slot(4, Some(ItemStack::new(items::LEATHER_BOOTS, 1)));
+        let _ = chest3.set_slot(5, Some(ItemStack::new(items::SHIELD, 1)));
+    }
+
+    // A small chest and a double chest next to the standard ones, fill the bottom half of the double chest
+    container_manager.create_chest(4, 1, Some("Small Chest".to_string()));
+    container_manager.create_double_chest(5, 6, None);
+
+    if let Some(chest6) = container_manager.containers.get_mut(&ContainerType::Chest(6)) {
+        let _ = chest6.set_slot(0, Some(ItemStack::new(items::STICK, 64)));
+        let _ = chest6.set_slot(26, Some(ItemStack::new(items::PAPER, 32)));
     }
 
     // Spawn camera
     commands.spawn(Camera2dBundle::default());
 
     // Create UI for the active containers
-    create_minecraft_ui(&mut commands, &asset_server, &container_manager);
+    create_minecraft_ui(&mut commands, &asset_server);
     create_held_item_ui(&mut commands, &asset_server);
     create_selected_item_ui(&mut commands, &asset_server);
+    create_item_tooltip_ui(&mut commands, &asset_server);
     create_hud(&mut commands, &asset_server);
 }
 
+/// Creates the sections container panels are placed in, the panels themselves are added by `sync_container_ui`
 pub fn create_minecraft_ui(
     commands: &mut Commands,
     asset_server: &Res<AssetServer>,
-    container_manager: &ContainerManager,
 ) {
     // Create a root container for all UI elements
     commands
@@ -99,7 +124,7 @@ pub fn create_minecraft_ui(
         })
         .with_children(|parent| {
             // Left side - chest selection panel
-            create_chest_selection_panel(parent, asset_server, container_manager);
+            create_chest_selection_panel(parent, asset_server);
 
             // Main UI area
             parent
@@ -132,24 +157,38 @@ pub fn create_minecraft_ui(
                         })
                         .with_children(|parent| {
 
-                            // Create top containers (chest, etc.) - positioned above player inventory
-                            for layout in &container_manager.layouts {
-                                if matches!(layout.position, ContainerPosition::Top) {
-                                    create_container_ui(parent, asset_server, layout);
-                                }
-                            }
+                            // Create top containers (chest, crafting grid, etc.) side by side - positioned above player inventory
+                            parent.spawn((
+                                NodeBundle {
+                                    style: Style {
+                                        flex_direction: FlexDirection::Row,
+                                        justify_content: JustifyContent::Center,
+                                        align_items: AlignItems::Center,
+                                        ..default()
+                                    },
+                                    ..default()
+                                },
+                                ContainerSection { position: ContainerPosition::Top },
+                            ));
 
-                            // Create center containers (player inventory) - positioned below chests
-                            for layout in &container_manager.layouts {
-                                if matches!(layout.position, ContainerPosition::Center) {
-                                    create_container_ui(parent, asset_server, layout);
-                                }
-                            }
+                            // Create center containers (equipment, player inventory) side by side - positioned below chests
+                            parent.spawn((
+                                NodeBundle {
+                                    style: Style {
+                                        flex_direction: FlexDirection::Row,
+                                        justify_content: JustifyContent::Center,
+                                        align_items: AlignItems::Center,
+                                        ..default()
+                                    },
+                                    ..default()
+                                },
+                                ContainerSection { position: ContainerPosition::Center },
+                            ));
                         });
 
                     // Bottom section (for hotbar)
-                    parent
-                        .spawn(NodeBundle {
+                    parent.spawn((
+                        NodeBundle {
                             style: Style {
                                 width: Val::Percent(100.0),
                                 flex_direction: FlexDirection::Column,
@@ -159,28 +198,23 @@ pub fn create_minecraft_ui(
                                 ..default()
                             },
                             ..default()
-                        })
-                        .with_children(|parent| {
-                            // Create bottom containers (hotbar)
-                            for layout in &container_manager.layouts {
-                                if matches!(layout.position, ContainerPosition::Bottom) {
-                                    create_container_ui(parent, asset_server, layout);
-                                }
-                            }
-                        });
+                        },
+                        ContainerSection { position: ContainerPosition::Bottom },
+                    ));
                 });
         });
 }
 
+/// Creates the panel of a container layout, to be added to the `ContainerSection` of its position
 pub fn create_container_ui(
-    parent: &mut ChildBuilder,
+    commands: &mut Commands,
     asset_server: &Res<AssetServer>,
     layout: &ContainerLayout
-) {
+) -> Entity {
     let container_width = (layout.columns as f32) * (SLOT_SIZE + SLOT_MARGIN * 2.0);
     let container_height = (layout.rows as f32) * (SLOT_SIZE + SLOT_MARGIN * 2.0);
 
-    parent
+    commands
         .spawn((
             NodeBundle {
                 style: Style {
@@ -192,63 +226,159 @@ pub fn create_container_ui(
                 },
                 ..default()
             },
-            ContainerUI { container_type: layout.container_type.clone() },
+            ContainerUI { layout: layout.clone() },
         ))
         .with_children(|parent| {
-            // Title (only if not empty)
+            // Header with the title and a sort button (only if the title is not empty)
             if !layout.title.is_empty() {
-                parent.spawn(TextBundle::from_section(
-                    &layout.title,
-                    TextStyle {
-                        font: asset_server.load("fonts/FiraSans-Bold.ttf"),
-                        font_size: 24.0,
-                        color: Color::WHITE,
-                    },
-                ));
+                parent
+                    .spawn(NodeBundle {
+                        style: Style {
+                            flex_direction: FlexDirection::Row,
+                            align_items: AlignItems::Center,
+                            column_gap: Val::Px(10.0),
+                            ..default()
+                        },
+                        ..default()
+                    })
+                    .with_children(|parent| {
+                        parent.spawn((
+                            TextBundle::from_section(
+                                &layout.title,
+                                TextStyle {
+                                    font: asset_server.load("fonts/FiraSans-Bold.ttf"),
+                                    font_size: 24.0,
+                                    color: Color::WHITE,
+                                },
+                            ),
+                            ContainerTitle,
+                        ));
+
+                        if layout.container_type.is_sortable() {
+                            create_sort_button(parent, asset_server, &layout.container_type);
+                        }
+                    });
             }
 
             // Container background
-            parent
-                .spawn(NodeBundle {
-                    style: Style {
-                        width: Val::Px(container_width + 20.0),
-                        height: Val::Px(container_height + 20.0),
-                        flex_direction: FlexDirection::Column,
-                        justify_content: JustifyContent::Center,
-                        align_items: AlignItems::Center,
-                        border: UiRect::all(Val::Px(2.0)),
-                        ..default()
-                    },
-                    background_color: Color::rgba(0.2, 0.2, 0.2, 0.9).into(),
-                    border_color: Color::rgb(0.6, 0.6, 0.6).into(),
+            let mut background = parent.spawn(NodeBundle {
+                style: Style {
+                    width: Val::Px(container_width + 20.0),
+                    height: Val::Px(container_height + 20.0),
+                    flex_direction: FlexDirection::Column,
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    border: UiRect::all(Val::Px(2.0)),
                     ..default()
-                })
-                .with_children(|parent| {
-                    // Create rows
-                    for row in 0..layout.rows {
-                        parent
-                            .spawn(NodeBundle {
+                },
+                background_color: Color::rgba(0.2, 0.2, 0.2, 0.9).into(),
+                border_color: Color::rgb(0.6, 0.6, 0.6).into(),
+                ..default()
+            });
+
+            // Chests and other containers above the inventory can get taller than the window
+            let scroll_view = background.id();
+            if layout.position == ContainerPosition::Top {
+                background.insert(ScrollView::new(layout.rows));
+            }
+
+            background.with_children(|parent| {
+                // Create rows
+                for row in 0..layout.rows {
+                    parent
+                        .spawn((
+                            NodeBundle {
                                 style: Style {
                                     flex_direction: FlexDirection::Row,
                                     justify_content: JustifyContent::Center,
                                     ..default()
                                 },
                                 ..default()
-                            })
-                            .with_children(|parent| {
-                                // Create slots in this row
-                                for col in 0..layout.columns {
-                                    let slot_index = row * layout.columns + col;
-                                    if slot_index < layout.slot_count {
-                                        create_inventory_slot(parent, slot_index, asset_server, &layout.container_type);
-                                    }
+                            },
+                            ScrollRow { view: scroll_view, row },
+                        ))
+                        .with_children(|parent| {
+                            // Create slots in this row, a double chest continues with its second half
+                            for col in 0..layout.columns {
+                                if let Some((container_type, slot_index)) = layout.slot_at(row * layout.columns + col) {
+                                    create_inventory_slot(parent, slot_index, asset_server, &container_type);
                                 }
-                            });
-                    }
-                });
+                            }
+                        });
+                }
+            });
+
+            // Furnaces show how much fuel is left and how far the current item has smelted
+            if let ContainerType::Furnace(furnace_id) = layout.container_type {
+                create_furnace_progress_bars(parent, furnace_id, container_width + 20.0);
+            }
+        })
+        .id()
+}
+
+fn create_sort_button(parent: &mut ChildBuilder, asset_server: &Res<AssetServer>, container_type: &ContainerType) {
+    parent
+        .spawn((
+            ButtonBundle {
+                style: Style {
+                    padding: UiRect::axes(Val::Px(6.0), Val::Px(2.0)),
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    border: UiRect::all(Val::Px(1.0)),
+                    ..default()
+                },
+                background_color: Color::rgb(0.4, 0.4, 0.4).into(),
+                border_color: Color::rgb(0.6, 0.6, 0.6).into(),
+                ..default()
+            },
+            SortButton { container_type: container_type.clone() },
+        ))
+        .with_children(|parent| {
+            parent.spawn(TextBundle::from_section(
+                "Sort",
+                TextStyle {
+                    font: asset_server.load("fonts/FiraSans-Bold.ttf"),
+                    font_size: 14.0,
+                    color: Color::WHITE,
+                },
+            ));
         });
 }
 
+fn create_furnace_progress_bars(parent: &mut ChildBuilder, furnace_id: u32, width: f32) {
+    for (kind, color) in [
+        (FurnaceProgressKind::Burn, Color::rgb(1.0, 0.5, 0.0)), // Orange for fuel
+        (FurnaceProgressKind::Cook, Color::rgb(0.9, 0.9, 0.9)), // White for smelting
+    ] {
+        // Bar background
+        parent
+            .spawn(NodeBundle {
+                style: Style {
+                    width: Val::Px(width),
+                    height: Val::Px(8.0),
+                    margin: UiRect::top(Val::Px(4.0)),
+                    ..default()
+                },
+                background_color: Color::rgba(0.1, 0.1, 0.1, 0.9).into(),
+                ..default()
+            })
+            .with_children(|parent| {
+                parent.spawn((
+                    NodeBundle {
+                        style: Style {
+                            width: Val::Percent(0.0),
+                            height: Val::Percent(100.0),
+                            ..default()
+                        },
+                        background_color: color.into(),
+                        ..default()
+                    },
+                    FurnaceProgressBar { furnace_id, kind },
+                ));
+            });
+    }
+}
+
 /// Create HUD with container switching buttons
 pub fn create_hud(commands: &mut Commands, asset_server: &Res<AssetServer>) {
     commands
@@ -278,7 +408,7 @@ pub fn create_hud(commands: &mut Commands, asset_server: &Res<AssetServer>) {
                 })
                 .with_children(|parent| {
                     parent.spawn(TextBundle::from_section(
-                        "E: Open/Close Inventory | C: Open/Close Chest | Esc: Close All",
+                        "E: Open/Close Inventory | C: Open/Close Chest | T: Open/Close Crafting Table | G: Open/Close Furnace | F: Swap Offhand | Hold Right-click: Use Item | WASD: Move | Q / Ctrl+Q: Drop | F3: Debug | F5: Save | F9: Load | Esc: Close All",
                         TextStyle {
                             font: asset_server.load("fonts/FiraSans-Bold.ttf"),
                             font_size: 16.0,
@@ -316,6 +446,7 @@ pub fn create_inventory_slot(
                 index,
                 container_type: container_type.clone()
             },
+            SlotRenderState::default(),
         ))
         .with_children(|parent| {
             // Container for item sprite and count
@@ -347,6 +478,71 @@ pub fn create_inventory_slot(
                         },
                         ItemSprite,
                     ));
+
+                    // Cracks over the sprite of items about to break, zig-zagging from the top-right corner
+                    parent
+                        .spawn((
+                            NodeBundle {
+                                style: Style {
+                                    position_type: PositionType::Absolute,
+                                    width: Val::Px(32.0),
+                                    height: Val::Px(32.0),
+                                    ..default()
+                                },
+                                background_color: Color::rgba(0.6, 0.0, 0.0, 0.2).into(),
+                                visibility: Visibility::Hidden,
+                                ..default()
+                            },
+                            SlotDamageOverlay,
+                        ))
+                        .with_children(|parent| {
+                            for (left, top, width, height) in [(22.0, 0.0, 2.0, 8.0), (16.0, 6.0, 8.0, 2.0), (16.0, 6.0, 2.0, 10.0), (10.0, 14.0, 8.0, 2.0), (10.0, 14.0, 2.0, 10.0)] {
+                                parent.spawn(NodeBundle {
+                                    style: Style {
+                                        position_type: PositionType::Absolute,
+                                        left: Val::Px(left),
+                                        top: Val::Px(top),
+                                        width: Val::Px(width),
+                                        height: Val::Px(height),
+                                        ..default()
+                                    },
+                                    background_color: Color::rgba(0.1, 0.05, 0.05, 0.85).into(),
+                                    ..default()
+                                });
+                            }
+                        });
+
+                    // Durability bar under the sprite
+                    parent
+                        .spawn((
+                            NodeBundle {
+                                style: Style {
+                                    display: Display::None,
+                                    position_type: PositionType::Absolute,
+                                    bottom: Val::Px(6.0),
+                                    left: Val::Px(10.0),
+                                    right: Val::Px(10.0),
+                                    height: Val::Px(4.0),
+                                    ..default()
+                                },
+                                background_color: Color::rgb(0.1, 0.1, 0.1).into(),
+                                ..default()
+                            },
+                            SlotDurabilityBar::default(),
+                        ))
+                        .with_children(|parent| {
+                            parent.spawn((
+                                NodeBundle {
+                                    style: Style {
+                                        width: Val::Percent(100.0),
+                                        height: Val::Percent(100.0),
+                                        ..default()
+                                    },
+                                    ..default()
+                                },
+                                SlotDurabilityFill,
+                            ));
+                        });
                     
                     // Item count text (positioned absolutely in bottom-right)
                     parent.spawn((
@@ -415,6 +611,71 @@ pub fn create_held_item_ui(commands: &mut Commands, asset_server: &Res<AssetServ
         });
 }
 
+/// Creates the tooltip shown while hovering an occupied slot, hidden until `update_item_tooltip` fills it
+pub fn create_item_tooltip_ui(commands: &mut Commands, asset_server: &Res<AssetServer>) {
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    display: Display::None,
+                    position_type: PositionType::Absolute,
+                    flex_direction: FlexDirection::Column,
+                    padding: UiRect::all(Val::Px(8.0)),
+                    border: UiRect::all(Val::Px(2.0)),
+                    max_width: Val::Px(320.0),
+                    ..default()
+                },
+                background_color: Color::rgba(0.1, 0.05, 0.15, 0.95).into(),
+                border_color: Color::rgb(0.3, 0.15, 0.5).into(),
+                z_index: ZIndex::Global(2000), // above the HUD
+                ..default()
+            },
+            ItemTooltip,
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                TextBundle::from_section(
+                    "",
+                    TextStyle {
+                        font: asset_server.load("fonts/FiraSans-Bold.ttf"),
+                        font_size: 14.0,
+                        color: Color::WHITE,
+                    },
+                ),
+                ItemTooltipText,
+            ));
+
+            parent
+                .spawn((
+                    NodeBundle {
+                        style: Style {
+                            width: Val::Percent(100.0),
+                            height: Val::Px(4.0),
+                            margin: UiRect::top(Val::Px(6.0)),
+                            ..default()
+                        },
+                        background_color: Color::rgb(0.1, 0.1, 0.1).into(),
+                        ..default()
+                    },
+                    TooltipDurabilityBar,
+                ))
+                .with_children(|parent| {
+                    parent.spawn((
+                        NodeBundle {
+                            style: Style {
+                                width: Val::Percent(100.0),
+                                height: Val::Percent(100.0),
+                                ..default()
+                            },
+                            background_color: Color::rgb(0.3, 0.9, 0.3).into(),
+                            ..default()
+                        },
+                        TooltipDurabilityFill,
+                    ));
+                });
+        });
+}
+
 /// Creates the UI that displays the selected hotbar item in the bottom-right corner
 pub fn create_selected_item_ui(commands: &mut Commands, asset_server: &Res<AssetServer>) {
     commands
@@ -423,6 +684,7 @@ pub fn create_selected_item_ui(commands: &mut Commands, asset_server: &Res<Asset
                 width: Val::Percent(100.0),
                 height: Val::Percent(100.0),
                 position_type: PositionType::Absolute,
+                flex_direction: FlexDirection::Column,
                 justify_content: JustifyContent::FlexEnd,
                 align_items: AlignItems::FlexEnd,
                 padding: UiRect::all(Val::Px(20.0)),
@@ -459,29 +721,57 @@ pub fn create_selected_item_ui(commands: &mut Commands, asset_server: &Res<Asset
                         },
                     ));
                 });
+
+            // Use progress of the selected consumable
+            parent
+                .spawn(NodeBundle {
+                    style: Style {
+                        width: Val::Px(SLOT_SIZE + 20.0),
+                        height: Val::Px(6.0),
+                        margin: UiRect::top(Val::Px(4.0)),
+                        ..default()
+                    },
+                    ..default()
+                })
+                .with_children(|parent| {
+                    parent.spawn((
+                        NodeBundle {
+                            style: Style {
+                                width: Val::Percent(0.0),
+                                height: Val::Percent(100.0),
+                                ..default()
+                            },
+                            background_color: Color::rgb(0.3, 0.9, 0.3).into(),
+                            ..default()
+                        },
+                        ItemUseProgressBar,
+                    ));
+                });
         });
 }
 
-/// Creates the left-side chest selection panel
+/// Creates the left-side chest selection panel, its buttons are added by `sync_container_ui`
 pub fn create_chest_selection_panel(
     parent: &mut ChildBuilder,
     asset_server: &Res<AssetServer>,
-    container_manager: &ContainerManager,
 ) {
     parent
-        .spawn(NodeBundle {
-            style: Style {
-                width: Val::Px(80.0),
-                height: Val::Percent(100.0),
-                flex_direction: FlexDirection::Column,
-                justify_content: JustifyContent::Center,
-                align_items: AlignItems::Center,
-                padding: UiRect::all(Val::Px(10.0)),
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    width: Val::Px(80.0),
+                    height: Val::Percent(100.0),
+                    flex_direction: FlexDirection::Column,
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    padding: UiRect::all(Val::Px(10.0)),
+                    ..default()
+                },
+                background_color: Color::rgba(0.1, 0.1, 0.1, 0.8).into(),
                 ..default()
             },
-            background_color: Color::rgba(0.1, 0.1, 0.1, 0.8).into(),
-            ..default()
-        })
+            ChestSelectionPanel,
+        ))
         .with_children(|parent| {
             // Title
             parent.spawn(TextBundle::from_section(
@@ -492,49 +782,67 @@ pub fn create_chest_selection_panel(
                     color: Color::WHITE,
                 },
             ));
+        });
+}
 
-            // Chest buttons
-            for &chest_id in &container_manager.available_chests {
-                let is_active = container_manager.active_chest_id == Some(chest_id);
-                let button_color = if is_active {
-                    Color::rgb(0.3, 0.6, 0.3) // Green for active
-                } else {
-                    Color::rgb(0.4, 0.4, 0.4) // Gray for inactive
-                };
+pub fn chest_button_color(is_active: bool) -> Color {
+    if is_active {
+        Color::rgb(0.3, 0.6, 0.3) // Green for active
+    } else {
+        Color::rgb(0.4, 0.4, 0.4) // Gray for inactive
+    }
+}
 
-                parent
-                    .spawn((
-                        ButtonBundle {
-                            style: Style {
-                                width: Val::Px(60.0),
-                                height: Val::Px(60.0),
-                                margin: UiRect::all(Val::Px(5.0)),
-                                justify_content: JustifyContent::Center,
-                                align_items: AlignItems::Center,
-                                border: UiRect::all(Val::Px(2.0)),
-                                ..default()
-                            },
-                            background_color: button_color.into(),
-                            border_color: Color::rgb(0.6, 0.6, 0.6).into(),
-                            ..default()
-                        },
-                        ChestButton { chest_id },
-                    ))
-                    .with_children(|parent| {
-                        parent.spawn(TextBundle::from_section(
-                            chest_id.to_string(),
-                            TextStyle {
-                                font: asset_server.load("fonts/FiraSans-Bold.ttf"),
-                                font_size: 18.0,
-                                color: Color::WHITE,
-                            },
-                        ));
-                    });
-            }
+pub fn create_chest_button(parent: &mut ChildBuilder, asset_server: &Res<AssetServer>, chest_id: u32, is_active: bool) {
+    parent
+        .spawn((
+            ButtonBundle {
+                style: Style {
+                    width: Val::Px(60.0),
+                    height: Val::Px(60.0),
+                    margin: UiRect::all(Val::Px(5.0)),
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    border: UiRect::all(Val::Px(2.0)),
+                    ..default()
+                },
+                background_color: chest_button_color(is_active).into(),
+                border_color: Color::rgb(0.6, 0.6, 0.6).into(),
+                ..default()
+            },
+            ChestButton { chest_id },
+        ))
+        .with_children(|parent| {
+            parent.spawn(TextBundle::from_section(
+                chest_id.to_string(),
+                TextStyle {
+                    font: asset_server.load("fonts/FiraSans-Bold.ttf"),
+                    font_size: 18.0,
+                    color: Color::WHITE,
+                },
+            ));
         });
 }
 
 #[derive(Component)]
 pub struct ChestButton {
     pub chest_id: u32,
-}
\ No newline at end of file
+}
+
+#[derive(Component)]
+pub struct SortButton {
+    pub container_type: ContainerType,
+}
+
+/// Node the panels of every container layout at `position` are placed in
+#[derive(Component)]
+pub struct ContainerSection {
+    pub position: ContainerPosition,
+}
+
+#[derive(Component)]
+pub struct ChestSelectionPanel;
+
+/// Header text of a container panel
+#[derive(Component)]
+pub struct ContainerTitle;
\ No newline at end of file
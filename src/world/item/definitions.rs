@@ -0,0 +1,261 @@
+use crate::world::item::intern::StaticInterner;
+use crate::world::item::item::{ConsumeEffect, EquipmentSlot, ItemProperties};
+use crate::world::item::registry::{ItemId, ItemRegistry};
+use bevy::asset::io::Reader;
+use bevy::asset::{Asset, AssetLoader, AsyncReadExt, LoadContext};
+use bevy::reflect::TypePath;
+use bevy::utils::BoxedFuture;
+use serde::Deserialize;
+use std::collections::HashSet;
+use std::fmt;
+
+/// The item spritesheet is 8 columns by 9 rows, see `setup_game`
+pub const SPRITESHEET_COLUMNS: u8 = 8;
+pub const SPRITESHEET_ROWS: u8 = 9;
+
+/// A single item as written by designers in a `.items.ron` / `.items.json` asset
+///
+/// Mirrors the arguments of `define_items!`, everything except the identifier,
+/// display name and sprite coordinates falls back to the `ItemProperties::new()` defaults.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ItemDefinition {
+    pub identifier: String,
+    pub display_name: String,
+    pub sprite_coords: (u8, u8),
+    #[serde(default = "default_max_stack_size")]
+    pub max_stack_size: u32,
+    #[serde(default)]
+    pub durability: Option<u64>,
+    #[serde(default)]
+    pub consumable: bool,
+    #[serde(default)]
+    pub offhand_equipable: bool,
+    #[serde(default)]
+    pub fuel_value: u32,
+    #[serde(default)]
+    pub smelt_result: Option<String>,
+    #[serde(default)]
+    pub equipment_slot: Option<EquipmentSlot>,
+    #[serde(default)]
+    pub use_duration_ms: u32,
+    #[serde(default)]
+    pub consume_effects: Vec<ConsumeEffectDefinition>,
+    #[serde(default)]
+    pub remainder: Option<String>,
+}
+
+/// Owned form of `ConsumeEffect` for item definition files
+#[derive(Debug, Clone, Deserialize)]
+pub enum ConsumeEffectDefinition {
+    RestoreHunger(u32),
+    RestoreHealth(u32),
+    Custom(String),
+}
+
+impl ConsumeEffectDefinition {
+    fn to_effect(&self, interner: &mut StaticInterner) -> ConsumeEffect {
+        match self {
+            ConsumeEffectDefinition::RestoreHunger(amount) => ConsumeEffect::RestoreHunger(*amount),
+            ConsumeEffectDefinition::RestoreHealth(amount) => ConsumeEffect::RestoreHealth(*amount),
+            ConsumeEffectDefinition::Custom(name) => ConsumeEffect::Custom(interner.intern_str(name)),
+        }
+    }
+}
+
+fn default_max_stack_size() -> u32 {
+    ItemProperties::new().max_stack_size
+}
+
+impl ItemDefinition {
+
+    /// Builds the `ItemProperties` for this definition
+    ///
+    /// `smelt_result`, `remainder` and the consume effects are interned like the strings in `ItemRegistry::register_runtime`.
+    pub fn properties(&self, interner: &mut StaticInterner) -> ItemProperties {
+        let mut properties = ItemProperties::new()
+            .max_stack_size(self.max_stack_size)
+            .consumable(self.consumable)
+            .offhand_equipable(self.offhand_equipable)
+            .fuel_value(self.fuel_value)
+            .use_duration_ms(self.use_duration_ms);
+
+        if let Some(durability) = self.durability {
+            properties = properties.durability(durability as u128);
+        }
+
+        if let Some(equipment_slot) = self.equipment_slot {
+            properties = properties.equipment_slot(equipment_slot);
+        }
+
+        if let Some(smelt_result) = &self.smelt_result {
+            properties = properties.smelt_result(interner.intern_str(smelt_result));
+        }
+
+        if let Some(remainder) = &self.remainder {
+            properties = properties.remainder(interner.intern_str(remainder));
+        }
+
+        if !self.consume_effects.is_empty() {
+            let effects: Vec<ConsumeEffect> = self.consume_effects.iter().map(|effect| effect.to_effect(interner)).collect();
+            properties = properties.consume_effects(interner.intern_effects(effects));
+        }
+
+        properties
+    }
+}
+
+/// A file full of item definitions, merged into the `ItemRegistry` whenever it is (re)loaded
+#[derive(Asset, TypePath, Debug, Deserialize)]
+pub struct ItemDefinitions {
+    pub items: Vec<ItemDefinition>,
+}
+
+#[derive(Debug)]
+pub enum ItemDefinitionError {
+    DuplicateIdentifier { identifier: String },
+    ConflictsWithBuiltin { identifier: String },
+    InvalidSpriteCoords { identifier: String, sprite_coords: (u8, u8) },
+    InvalidMaxStackSize { identifier: String },
+}
+
+impl fmt::Display for ItemDefinitionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ItemDefinitionError::DuplicateIdentifier { identifier } => {
+                write!(f, "item \"{}\" is defined more than once", identifier)
+            }
+            ItemDefinitionError::ConflictsWithBuiltin { identifier } => {
+                write!(f, "item \"{}\" is already defined by define_items!", identifier)
+            }
+            ItemDefinitionError::InvalidSpriteCoords { identifier, sprite_coords } => {
+                write!(
+                    f,
+                    "item \"{}\" uses sprite {:?}, outside the {}x{} spritesheet",
+                    identifier, sprite_coords, SPRITESHEET_COLUMNS, SPRITESHEET_ROWS
+                )
+            }
+            ItemDefinitionError::InvalidMaxStackSize { identifier } => {
+                write!(f, "item \"{}\" has a max_stack_size of 0", identifier)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ItemDefinitionError {}
+
+#[derive(Debug)]
+pub enum ItemDefinitionsLoaderError {
+    Io(std::io::Error),
+    Ron(ron::error::SpannedError),
+    Json(serde_json::Error),
+}
+
+impl fmt::Display for ItemDefinitionsLoaderError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ItemDefinitionsLoaderError::Io(error) => write!(f, "could not read item definitions: {}", error),
+            ItemDefinitionsLoaderError::Ron(error) => write!(f, "invalid RON item definitions: {}", error),
+            ItemDefinitionsLoaderError::Json(error) => write!(f, "invalid JSON item definitions: {}", error),
+        }
+    }
+}
+
+impl std::error::Error for ItemDefinitionsLoaderError {}
+
+/// Loads `*.items.ron` and `*.items.json` files into `ItemDefinitions`
+#[derive(Default)]
+pub struct ItemDefinitionsLoader;
+
+impl AssetLoader for ItemDefinitionsLoader {
+    type Asset = ItemDefinitions;
+    type Settings = ();
+    type Error = ItemDefinitionsLoaderError;
+
+    fn load<'a>(
+        &'a self,
+        reader: &'a mut Reader,
+        _settings: &'a Self::Settings,
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<Self::Asset, Self::Error>> {
+        Box::pin(async move {
+            let mut bytes = Vec::new();
+            reader.read_to_end(&mut bytes).await.map_err(ItemDefinitionsLoaderError::Io)?;
+
+            let is_json = load_context.path().extension().is_some_and(|extension| extension == "json");
+            if is_json {
+                serde_json::from_slice(&bytes).map_err(ItemDefinitionsLoaderError::Json)
+            } else {
+                ron::de::from_bytes(&bytes).map_err(ItemDefinitionsLoaderError::Ron)
+            }
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["items.ron", "items.json"]
+    }
+}
+
+impl ItemRegistry {
+
+    /// Merges data-driven definitions into the registry
+    ///
+    /// Valid definitions are registered (or updated in place when an asset is hot-reloaded),
+    /// invalid ones are skipped and reported. Returns the ids of every item that was added or changed.
+    pub fn merge_definitions(&mut self, definitions: &ItemDefinitions) -> (Vec<ItemId>, Vec<ItemDefinitionError>) {
+        let mut merged = Vec::new();
+        let mut errors = Vec::new();
+        let mut seen = HashSet::new();
+
+        for definition in &definitions.items {
+            let identifier = &definition.identifier;
+
+            if !seen.insert(identifier.as_str()) {
+                errors.push(ItemDefinitionError::DuplicateIdentifier { identifier: identifier.clone() });
+                continue;
+            }
+
+            let (x, y) = definition.sprite_coords;
+            if x >= SPRITESHEET_COLUMNS || y >= SPRITESHEET_ROWS {
+                errors.push(ItemDefinitionError::InvalidSpriteCoords {
+                    identifier: identifier.clone(),
+                    sprite_coords: definition.sprite_coords,
+                });
+                continue;
+            }
+
+            if definition.max_stack_size == 0 {
+                errors.push(ItemDefinitionError::InvalidMaxStackSize { identifier: identifier.clone() });
+                continue;
+            }
+
+            match self.id_of(identifier) {
+                Some(id) if self.is_builtin(id) => {
+                    errors.push(ItemDefinitionError::ConflictsWithBuiltin { identifier: identifier.clone() });
+                }
+                Some(id) => {
+                    // hot reload of an item we registered from data earlier
+                    let properties = definition.properties(self.interner_mut());
+                    if self.update_runtime(id, &definition.display_name, properties, definition.sprite_coords) {
+                        merged.push(id);
+                    }
+                }
+                None => {
+                    let properties = definition.properties(self.interner_mut());
+                    let registered = self.register_runtime(
+                        identifier.clone(),
+                        definition.display_name.clone(),
+                        properties,
+                        definition.sprite_coords,
+                    );
+
+                    match registered {
+                        Ok(id) => merged.push(id),
+                        Err(_) => errors.push(ItemDefinitionError::DuplicateIdentifier { identifier: identifier.clone() }),
+                    }
+                }
+            }
+        }
+
+        (merged, errors)
+    }
+}
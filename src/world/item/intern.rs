@@ -0,0 +1,35 @@
+use crate::world::item::item::ConsumeEffect;
+use std::collections::{HashMap, HashSet};
+
+/// Leaks every distinct string and effect list once, for data an `Item` borrows for `'static`
+///
+/// Item definitions are read again on every hot reload, so leaking their strings each time would
+/// grow memory with every save of the asset file. Interned values are reused instead.
+#[derive(Default)]
+pub struct StaticInterner {
+    strings: HashSet<&'static str>,
+    effects: HashMap<Vec<ConsumeEffect>, &'static [ConsumeEffect]>,
+}
+
+impl StaticInterner {
+    pub fn intern_str(&mut self, value: &str) -> &'static str {
+        if let Some(interned) = self.strings.get(value) {
+            return interned;
+        }
+
+        let leaked: &'static str = Box::leak(value.to_string().into_boxed_str());
+        self.strings.insert(leaked);
+        leaked
+    }
+
+    /// Effects should already use interned names, otherwise equal lists are told apart by nothing but their names
+    pub fn intern_effects(&mut self, effects: Vec<ConsumeEffect>) -> &'static [ConsumeEffect] {
+        if let Some(interned) = self.effects.get(&effects) {
+            return interned;
+        }
+
+        let leaked: &'static [ConsumeEffect] = Box::leak(effects.clone().into_boxed_slice());
+        self.effects.insert(effects, leaked);
+        leaked
+    }
+}
@@ -1,9 +1,35 @@
+use serde::Deserialize;
+
+/// Which armour slot an item is worn in
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Deserialize)]
+pub enum EquipmentSlot {
+    Head,
+    Chest,
+    Legs,
+    Feet,
+}
+
+/// What consuming an item does, applied by gameplay systems listening for `ItemConsumedEvent`
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum ConsumeEffect {
+    RestoreHunger(u32),
+    RestoreHealth(u32),
+    Custom(&'static str), // handled by whichever system knows the name
+}
+
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub struct ItemProperties {
     pub max_stack_size: u32,
     pub durability: Option<u128>,
     pub is_consumable: bool,
-    pub offhand_equipable: bool
+    pub offhand_equipable: bool,
+    pub fuel_value: u32, // how many items one unit of this fuel smelts, 0 = not a fuel
+    pub smelt_result: Option<&'static str>, // identifier of the item this smelts into
+    pub equipment_slot: Option<EquipmentSlot>, // armour slot this item is worn in
+    pub use_duration_ms: u32, // how long a consumable has to be used for, 0 = instant
+    pub consume_effects: &'static [ConsumeEffect],
+    pub remainder: Option<&'static str>, // identifier of the item left behind after consuming this one
+    pub storage: Option<&'static str>, // identifier of the portable container kind this item carries, like a backpack
 }
 
 impl ItemProperties {
@@ -13,7 +39,14 @@ impl ItemProperties {
             max_stack_size: 64,
             durability: None,
             is_consumable: false,
-            offhand_equipable: false
+            offhand_equipable: false,
+            fuel_value: 0,
+            smelt_result: None,
+            equipment_slot: None,
+            use_duration_ms: 0,
+            consume_effects: &[],
+            remainder: None,
+            storage: None,
         }
     }
 
@@ -36,6 +69,44 @@ impl ItemProperties {
         self.offhand_equipable = offhand_equipable;
         self
     }
+
+    pub const fn fuel_value(mut self, fuel_value: u32) -> Self {
+        self.fuel_value = fuel_value;
+        self
+    }
+
+    pub const fn smelt_result(mut self, identifier: &'static str) -> Self {
+        self.smelt_result = Some(identifier);
+        self
+    }
+
+    pub const fn equipment_slot(mut self, slot: EquipmentSlot) -> Self {
+        self.equipment_slot = Some(slot);
+        self
+    }
+
+    pub const fn use_duration_ms(mut self, duration_ms: u32) -> Self {
+        self.use_duration_ms = duration_ms;
+        self
+    }
+
+    pub const fn consume_effects(mut self, effects: &'static [ConsumeEffect]) -> Self {
+        self.consume_effects = effects;
+        self
+    }
+
+    pub const fn remainder(mut self, identifier: &'static str) -> Self {
+        self.remainder = Some(identifier);
+        self
+    }
+
+    /// Gives every stack of this item its own container of the portable kind registered as `kind`,
+    /// see `ContainerKind::portable`. Such items should have a max stack size of 1, splitting a stack
+    /// would share its contents
+    pub const fn storage(mut self, kind: &'static str) -> Self {
+        self.storage = Some(kind);
+        self
+    }
 }
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
@@ -4,8 +4,21 @@ use crate::define_items;
 define_items! {
     BOW => "bow" as "Bow" @ (0, 2): ItemProperties::new().durability(120).max_stack_size(1),
     IRON_SWORD => "iron_sword" as "Iron Sword" @ (0, 0): ItemProperties::new().durability(120).max_stack_size(1), // todo durability implies stack size 1
-    APPLE => "apple" as "Apple" @ (0, 8): ItemProperties::new().consumable(true),
+    APPLE => "apple" as "Apple" @ (0, 8): ItemProperties::new().consumable(true).use_duration_ms(1600).consume_effects(&[ConsumeEffect::RestoreHunger(4)]).smelt_result("apple_pie"),
     GLASS_BOTTLE => "glass_bottle" as "Glass Bottle" @ (7, 3): ItemProperties::new().max_stack_size(16).offhand_equipable(true), // todo no need to specify true when calling implies the same thing
-    CHEESE => "cheese" as "Cheese" @ (1, 8): ItemProperties::new().consumable(true),
+    CHEESE => "cheese" as "Cheese" @ (1, 8): ItemProperties::new().consumable(true).use_duration_ms(1200).consume_effects(&[ConsumeEffect::RestoreHunger(3)]),
     RING => "ring" as "Ring" @ (0, 5): ItemProperties::new(),
+    STICK => "stick" as "Stick" @ (4, 2): ItemProperties::new().fuel_value(1),
+    PAPER => "paper" as "Paper" @ (6, 4): ItemProperties::new().fuel_value(1),
+    SCROLL => "scroll" as "Scroll" @ (4, 4): ItemProperties::new().max_stack_size(16),
+    WOODEN_SHIELD => "wooden_shield" as "Wooden Shield" @ (0, 3): ItemProperties::new().durability(60).max_stack_size(1).offhand_equipable(true),
+    APPLE_PIE => "apple_pie" as "Apple Pie" @ (3, 8): ItemProperties::new().consumable(true).use_duration_ms(1600).consume_effects(&[ConsumeEffect::RestoreHunger(8)]),
+    IRON_HELMET => "iron_helmet" as "Iron Helmet" @ (4, 5): ItemProperties::new().durability(165).max_stack_size(1).equipment_slot(EquipmentSlot::Head),
+    IRON_CHESTPLATE => "iron_chestplate" as "Iron Chestplate" @ (1, 7): ItemProperties::new().durability(240).max_stack_size(1).equipment_slot(EquipmentSlot::Chest),
+    LEATHER_BOOTS => "leather_boots" as "Leather Boots" @ (0, 6): ItemProperties::new().durability(65).max_stack_size(1).equipment_slot(EquipmentSlot::Feet),
+    IRON_BOOTS => "iron_boots" as "Iron Boots" @ (1, 6): ItemProperties::new().durability(195).max_stack_size(1).equipment_slot(EquipmentSlot::Feet),
+    SHIELD => "shield" as "Shield" @ (1, 3): ItemProperties::new().durability(336).max_stack_size(1).offhand_equipable(true),
+    HEALTH_POTION => "health_potion" as "Health Potion" @ (5, 3): ItemProperties::new().max_stack_size(1).consumable(true).use_duration_ms(1600).consume_effects(&[ConsumeEffect::RestoreHealth(8)]).remainder("glass_bottle"),
+    RING_STAFF => "ring_staff" as "Ring Staff" @ (6, 2): ItemProperties::new().max_stack_size(1),
+    BACKPACK => "backpack" as "Backpack" @ (0, 7): ItemProperties::new().max_stack_size(1).storage("backpack"),
 }
\ No newline at end of file
@@ -0,0 +1,64 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// An enchantment on a single stack, identified like items are, e.g. `("sharpness", 2)`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Enchantment {
+    pub identifier: String,
+    pub level: u32,
+}
+
+/// Data that belongs to one particular stack rather than its `Item`, like a loot roll's name
+///
+/// Stacks only merge if their metadata is equal, so two swords named differently never stack.
+/// Attributes are kept sorted, making equality and the saved order independent of insertion order.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ItemMetadata {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub custom_name: Option<String>, // shown instead of the item's display name
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub lore: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub enchantments: Vec<Enchantment>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub attributes: BTreeMap<String, String>, // anything else, e.g. who crafted the item
+}
+
+impl ItemMetadata {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn custom_name(mut self, name: impl Into<String>) -> Self {
+        self.custom_name = Some(name.into());
+        self
+    }
+
+    pub fn lore_line(mut self, line: impl Into<String>) -> Self {
+        self.lore.push(line.into());
+        self
+    }
+
+    pub fn enchantment(mut self, identifier: impl Into<String>, level: u32) -> Self {
+        self.enchantments.push(Enchantment { identifier: identifier.into(), level });
+        self
+    }
+
+    pub fn attribute(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.attributes.insert(key.into(), value.into());
+        self
+    }
+
+    /// Checks if there is nothing to store, such metadata is dropped by `ItemStack::with_metadata`
+    pub fn is_empty(&self) -> bool {
+        self.custom_name.is_none() && self.lore.is_empty() && self.enchantments.is_empty() && self.attributes.is_empty()
+    }
+
+    /// The lines shown below an item's name: enchantments first, then lore
+    pub fn description_lines(&self) -> Vec<String> {
+        self.enchantments.iter()
+            .map(|enchantment| format!("{} {}", enchantment.identifier, enchantment.level))
+            .chain(self.lore.iter().cloned())
+            .collect()
+    }
+}
@@ -1,5 +1,10 @@
+pub mod definitions;
+pub mod intern;
 pub mod item;
+pub mod metadata;
 pub mod items;
+pub mod registry;
+pub mod world_item;
 
 /// `define_items` is a declarative macro. (a.k.a. a "macro by example")
 ///
@@ -0,0 +1,181 @@
+use crate::world::item::intern::StaticInterner;
+use crate::world::item::item::{Item, ItemProperties};
+use crate::world::item::items::ITEMS;
+use bevy::prelude::Resource;
+use std::collections::HashMap;
+use std::fmt;
+
+/// Numeric item id, assigned in registration order and stable for the lifetime of the registry
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ItemId(pub u32);
+
+#[derive(Debug, Clone)]
+pub enum RegistryError {
+    DuplicateIdentifier { identifier: String },
+}
+
+impl fmt::Display for RegistryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RegistryError::DuplicateIdentifier { identifier } => {
+                write!(f, "an item with identifier \"{}\" is already registered", identifier)
+            }
+        }
+    }
+}
+
+impl std::error::Error for RegistryError {}
+
+/// Every item known to the game, looked up by `identifier` or numeric `ItemId`
+///
+/// The items from `define_items!` are always registered first (the built-in set),
+/// anything registered afterwards (mods, data files, ...) is appended after them.
+#[derive(Resource)]
+pub struct ItemRegistry {
+    items: Vec<Item>,
+    ids: HashMap<&'static str, ItemId>,
+    builtin_count: usize,
+    interner: StaticInterner, // strings of runtime items, reused across hot reloads
+}
+
+impl Default for ItemRegistry {
+    fn default() -> Self {
+        Self::with_builtin_items()
+    }
+}
+
+impl ItemRegistry {
+
+    /// Creates a registry without any items, not even the built-in ones
+    pub fn empty() -> Self {
+        Self {
+            items: Vec::new(),
+            ids: HashMap::new(),
+            builtin_count: 0,
+            interner: StaticInterner::default(),
+        }
+    }
+
+    /// Creates a registry holding every item from `define_items!`
+    pub fn with_builtin_items() -> Self {
+        let mut registry = Self::empty();
+
+        for (_, item) in ITEMS {
+            // identifiers in `define_items!` are unique, a duplicate here is a programming error
+            registry.register(**item).expect("duplicate identifier in define_items!");
+        }
+
+        registry.builtin_count = registry.items.len();
+        registry
+    }
+
+    /// Registers an item, failing if its identifier is already taken
+    pub fn register(&mut self, item: Item) -> Result<ItemId, RegistryError> {
+        if self.ids.contains_key(item.identifier) {
+            return Err(RegistryError::DuplicateIdentifier {
+                identifier: item.identifier.to_string(),
+            });
+        }
+
+        let id = ItemId(self.items.len() as u32);
+        self.ids.insert(item.identifier, id);
+        self.items.push(item);
+        Ok(id)
+    }
+
+    /// Registers an item whose identifier and name are only known at runtime
+    ///
+    /// `Item` borrows its strings for `'static`, so they are interned: each distinct string is
+    /// leaked once and reused by later registrations and updates.
+    pub fn register_runtime(
+        &mut self,
+        identifier: impl Into<String>,
+        display_name: impl Into<String>,
+        properties: ItemProperties,
+        sprite_coords: (u8, u8),
+    ) -> Result<ItemId, RegistryError> {
+        let identifier = identifier.into();
+        if self.ids.contains_key(identifier.as_str()) {
+            return Err(RegistryError::DuplicateIdentifier { identifier });
+        }
+
+        let identifier = self.interner.intern_str(&identifier);
+        let display_name = self.interner.intern_str(&display_name.into());
+        self.register(Item {
+            identifier,
+            display_name,
+            properties,
+            sprite_coords,
+        })
+    }
+
+    /// Replaces the data of a runtime-registered item in place, keeping its id and identifier
+    ///
+    /// Returns `true` if anything changed. Built-in items can't be updated.
+    pub fn update_runtime(
+        &mut self,
+        id: ItemId,
+        display_name: &str,
+        properties: ItemProperties,
+        sprite_coords: (u8, u8),
+    ) -> bool {
+        if self.is_builtin(id) {
+            return false;
+        }
+
+        let Some(item) = self.items.get_mut(id.0 as usize) else {
+            return false;
+        };
+
+        if item.display_name == display_name && item.properties == properties && item.sprite_coords == sprite_coords {
+            return false;
+        }
+
+        if item.display_name != display_name {
+            item.display_name = self.interner.intern_str(display_name);
+        }
+        item.properties = properties;
+        item.sprite_coords = sprite_coords;
+        true
+    }
+
+    /// Interns strings and effect lists for the properties of runtime items
+    pub fn interner_mut(&mut self) -> &mut StaticInterner {
+        &mut self.interner
+    }
+
+    /// Looks up an item by its identifier, e.g. `"iron_sword"`
+    pub fn get(&self, identifier: &str) -> Option<Item> {
+        self.id_of(identifier).and_then(|id| self.get_by_id(id))
+    }
+
+    pub fn get_by_id(&self, id: ItemId) -> Option<Item> {
+        self.items.get(id.0 as usize).copied()
+    }
+
+    pub fn id_of(&self, identifier: &str) -> Option<ItemId> {
+        self.ids.get(identifier).copied()
+    }
+
+    pub fn contains(&self, identifier: &str) -> bool {
+        self.ids.contains_key(identifier)
+    }
+
+    /// Checks if an item came from `define_items!` rather than being registered at runtime
+    pub fn is_builtin(&self, id: ItemId) -> bool {
+        (id.0 as usize) < self.builtin_count
+    }
+
+    /// Iterates over every item in registration order
+    pub fn iter(&self) -> impl Iterator<Item = (ItemId, &Item)> {
+        self.items.iter().enumerate().map(|(index, item)| (ItemId(index as u32), item))
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+}
@@ -0,0 +1,60 @@
+use crate::world::inventory::item_stack::ItemStack;
+use bevy::math::Vec2;
+use bevy::prelude::{Component, Event};
+
+/// Seconds before a dropped item can be picked up again
+pub const PICKUP_DELAY_SECS: f32 = 1.5;
+
+/// Distance from the player at which world items are picked up
+pub const PICKUP_RADIUS: f32 = 40.0;
+
+/// Speed dropped items are thrown at, they slow down by `WORLD_ITEM_FRICTION` per second
+pub const THROW_SPEED: f32 = 250.0;
+pub const WORLD_ITEM_FRICTION: f32 = 4.0;
+
+/// Size of world item and player sprites
+pub const WORLD_ITEM_SIZE: f32 = 32.0;
+pub const PLAYER_SIZE: f32 = 40.0;
+
+/// Player movement speed in pixels per second
+pub const PLAYER_SPEED: f32 = 200.0;
+
+/// An item stack lying in the world
+#[derive(Component, Debug, Clone)]
+pub struct WorldItem {
+    pub stack: ItemStack,
+    pub pickup_delay: f32,
+    pub velocity: Vec2,
+}
+
+impl WorldItem {
+    pub fn thrown(stack: ItemStack, direction: Vec2) -> Self {
+        Self {
+            stack,
+            pickup_delay: PICKUP_DELAY_SECS,
+            velocity: direction.normalize_or_zero() * THROW_SPEED,
+        }
+    }
+
+    pub fn can_be_picked_up(&self) -> bool {
+        self.pickup_delay <= 0.0
+    }
+}
+
+/// The player picking up world items, moved with WASD
+#[derive(Component, Debug, Clone)]
+pub struct Player {
+    pub facing: Vec2, // direction items are thrown in
+}
+
+impl Default for Player {
+    fn default() -> Self {
+        Self { facing: Vec2::X }
+    }
+}
+
+/// Throws a stack out of the inventory in front of the player
+#[derive(Event, Debug, Clone)]
+pub struct DropItemEvent {
+    pub stack: ItemStack,
+}
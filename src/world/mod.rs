@@ -1,2 +1,3 @@
 pub mod item;
-pub mod inventory;
\ No newline at end of file
+pub mod inventory;
+pub mod crafting;
\ No newline at end of file